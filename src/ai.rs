@@ -1,6 +1,7 @@
 use crate::error::ReviseResult;
 
 pub mod gemini;
+pub mod redact;
 
 pub(crate) trait AI<T> {
     async fn generate_response(&self, input: &str) -> ReviseResult<T>;
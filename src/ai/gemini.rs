@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
 use super::AI;
-use crate::ReviseResult;
+use crate::{ReviseResult, error::ReviseError};
 
 #[derive(Debug, Clone)]
 pub struct Gemini {
@@ -147,7 +147,11 @@ impl Gemini {
             .post(&self.url)
             .header(reqwest::header::USER_AGENT, "crate/revise")
             .header(reqwest::header::CONTENT_TYPE, "application/json");
-        let result = request_builder.json(&txt_request).send().await?;
+        let result = request_builder
+            .json(&txt_request)
+            .send()
+            .await
+            .map_err(|e| ReviseError::AiNetwork(e.to_string()))?;
         match result.status() {
             reqwest::StatusCode::OK => {
                 let response = result.json::<GeminiResponse>().await?;
@@ -174,11 +178,31 @@ impl Gemini {
 
                 Ok(m)
             }
-            _ => Err(anyhow::anyhow!(
-                "Failed to get response from Gemini API: {}, response: {}",
-                result.status(),
-                result.text().await?
-            )),
+            status => {
+                let text = result.text().await.unwrap_or_default();
+                let msg = format!("{status}, response: {text}");
+                Err(match status {
+                    reqwest::StatusCode::UNAUTHORIZED
+                    | reqwest::StatusCode::FORBIDDEN => {
+                        ReviseError::AiAuth(msg)
+                    }
+                    // Gemini answers an unknown key with a plain 400
+                    reqwest::StatusCode::BAD_REQUEST
+                        if text.contains("API_KEY_INVALID") =>
+                    {
+                        ReviseError::AiAuth(msg)
+                    }
+                    reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                        ReviseError::AiQuota(msg)
+                    }
+                    _ => {
+                        return Err(anyhow::anyhow!(
+                            "Failed to get response from Gemini API: {msg}"
+                        ));
+                    }
+                }
+                .into())
+            }
         }
     }
 }
@@ -355,7 +379,7 @@ mod tests {
 
     use super::*;
 
-    #[ignore]
+    #[ignore = "requires a Gemini API key and network access"]
     #[tokio::test]
     #[allow(clippy::needless_return)]
     async fn test_gemini_call() {
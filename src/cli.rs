@@ -1,6 +1,12 @@
-use clap::{ArgAction, ArgGroup, Parser};
+use std::{path::PathBuf, str::FromStr};
 
-use crate::config;
+use clap::{ArgAction, ArgGroup, Args, Parser, Subcommand, ValueEnum};
+
+use crate::{
+    config::{self, preset::Preset},
+    error::{ReviseError, ReviseResult},
+    hook::{HookFilter, HookType},
+};
 
 #[derive(Debug, Parser)]
 #[clap(
@@ -18,7 +24,12 @@ use crate::config;
         .args(&["message"])
         .conflicts_with_all(&["generate", "translate", "path", "exclude", "include"])
 ))]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Cli {
+    /// Run as if started in DIR, like `git -C`
+    #[clap(short = 'C', value_name = "DIR")]
+    pub directory: Vec<PathBuf>,
+
     /// Generate AI-assisted commit message
     #[clap(short = 'g', long = "generate", action = ArgAction::SetTrue)]
     pub generate: bool,
@@ -31,6 +42,10 @@ pub struct Cli {
     #[clap(short = 'a', long = "add", num_args = 0.., default_missing_value = ".", value_delimiter = ' ')]
     pub path: Vec<String>,
 
+    /// Interactively pick the hunks to stage, like `git add -p`
+    #[clap(short = 'p', long = "patch", action = ArgAction::SetTrue)]
+    pub patch: bool,
+
     /// Exclude files from being added
     #[clap(short = 'x', long = "exclude")]
     pub exclude: Vec<String>,
@@ -39,20 +54,148 @@ pub struct Cli {
     #[clap(short = 'i', long = "include")]
     pub include: Vec<String>,
 
+    /// Amend the last commit, pre-filling the prompts from its message
+    #[clap(long = "amend", action = ArgAction::SetTrue)]
+    pub amend: bool,
+
     /// Specify commit message
     #[clap(short = 'm', long = "message")]
     pub message: Option<String>,
+
+    /// Don't run any of the configured hooks
+    #[clap(long = "no-hooks", action = ArgAction::SetTrue)]
+    pub no_hooks: bool,
+
+    /// Skip the configured hook with this name or command
+    #[clap(long = "skip-hook", value_name = "NAME")]
+    pub skip_hook: Vec<String>,
+
+    /// Only run the configured hooks of this phase, e.g. `pre-commit`
+    #[clap(long = "only-hook", value_name = "PHASE", value_parser = HookType::from_str)]
+    pub only_hook: Vec<HookType>,
+
+    /// Skip the repository's own git hooks, like `git commit --no-verify`
+    #[clap(short = 'n', long = "no-verify", action = ArgAction::SetTrue)]
+    pub no_verify: bool,
+
     // /// Revise commit message
     // #[clap(short = 'r', long = "repeat", action = ArgAction::SetTrue)]
     // pub repeat: bool,
+    #[clap(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Commands {
+    /// Reword the messages of existing commits
+    Reword(RewordArgs),
+    /// Check commit messages against the configured rules
+    Lint(LintArgs),
+    /// Run `git merge`, then the post-merge hooks
+    Merge(GitArgs),
+    /// Run `git pull`, then the post-merge hooks
+    Pull(GitArgs),
+    /// Manage the git hooks that run revise on a plain `git commit`
+    Hook {
+        #[clap(subcommand)]
+        command: HookCommand,
+    },
+    /// Create, inspect and check the revise.toml config
+    Config {
+        #[clap(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ConfigCommand {
+    /// Write a commented starter revise.toml at the repository root
+    Init {
+        /// Overwrite an existing revise.toml
+        #[clap(short = 'f', long = "force", action = ArgAction::SetTrue)]
+        force: bool,
+        /// Preset to start from, asked for when not given
+        #[clap(long = "preset", value_enum)]
+        preset: Option<Preset>,
+    },
+    /// Print the effective config and the layer each value comes from
+    Show,
+    /// Check the config files for mistakes
+    Validate,
+    /// List the config files that are searched, in merge order
+    Path,
+    /// Print the JSON Schema of revise.toml, for editor completion
+    Schema,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct GitArgs {
+    /// Arguments passed on to git
+    #[clap(trailing_var_arg = true, allow_hyphen_values = true)]
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum HookCommand {
+    /// Install the `prepare-commit-msg` and `commit-msg` git hooks
+    Install {
+        /// Replace existing hooks, keeping them as `<hook>.revise-backup`
+        #[clap(short = 'f', long = "force", action = ArgAction::SetTrue)]
+        force: bool,
+    },
+    /// Remove the installed git hooks, restoring any backup
+    Uninstall,
+    /// Run by the `prepare-commit-msg` hook: prompts and writes the message
+    #[clap(hide = true)]
+    PrepareCommitMsg {
+        file: String,
+        source: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct RewordArgs {
+    /// Commits to reword, e.g. `HEAD~3..` or `main..HEAD`; a single
+    /// revision means everything after it
+    pub range: String,
+
+    /// Also rewrite commits that were already pushed
+    #[clap(short = 'f', long = "force", action = ArgAction::SetTrue)]
+    pub force: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct LintArgs {
+    /// File holding the message, `-` or nothing to read stdin
+    #[clap(conflicts_with = "range")]
+    pub file: Option<String>,
+
+    /// Check the commits of a revision range instead, e.g. `main..HEAD`
+    #[clap(short = 'r', long = "range")]
+    pub range: Option<String>,
+
+    /// Output format
+    #[clap(long = "format", value_enum, default_value_t = LintFormat::Text)]
+    pub format: LintFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LintFormat {
+    Text,
+    Json,
 }
 
 #[derive(Debug)]
 pub struct ReviseCommands {
+    pub command: Option<Commands>,
     pub ai: Option<AICommand>,
     pub add: Vec<String>,
+    pub patch: bool,
     pub excludes: Vec<String>,
     pub message: Option<String>,
+    pub amend: bool,
+    pub hooks: HookFilter,
+    pub no_verify: bool,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -73,8 +216,28 @@ pub enum AICommand {
 //     pub update: bool,
 // }
 
-pub fn parse_command() -> ReviseCommands {
-    let cli = Cli::parse();
+impl Cli {
+    /// `config` subcommands run without a valid config, to help fix it.
+    pub const fn is_config_command(&self) -> bool {
+        matches!(self.command, Some(Commands::Config { .. }))
+    }
+
+    /// Moves into the `-C` directories, each relative to the one before.
+    /// Empty ones are ignored, as git does.
+    pub fn change_dir(&self) -> ReviseResult<()> {
+        for dir in self.directory.iter().filter(|d| !d.as_os_str().is_empty()) {
+            std::env::set_current_dir(dir).map_err(|e| {
+                ReviseError::Validation(format!(
+                    "Cannot change to '{}': {e}",
+                    dir.display()
+                ))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+pub fn parse_command(cli: Cli) -> ReviseCommands {
     let cfg = config::get_config();
 
     let mut combined_excludes: Vec<String> = cfg.exclude_files.clone();
@@ -85,14 +248,23 @@ pub fn parse_command() -> ReviseCommands {
     }
 
     ReviseCommands {
+        command: cli.command,
         ai: if cli.generate {
             Some(AICommand::Generate)
         } else {
             cli.translate.map(AICommand::Translate)
         },
         add: cli.path,
+        patch: cli.patch,
         excludes: combined_excludes,
         message: cli.message,
+        amend: cli.amend,
+        hooks: HookFilter {
+            disabled: cli.no_hooks,
+            skip: cli.skip_hook,
+            only: cli.only_hook,
+        },
+        no_verify: cli.no_verify,
         // repeat: cli.repeat,
     }
 }
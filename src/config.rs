@@ -1,77 +1,337 @@
-use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::OnceLock};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::OnceLock,
+};
 
-use colored::Colorize;
 use realme::{Adaptor, EnvParser, EnvSource, FileSource, Realme, TomlParser};
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
+use self::preset::Preset;
 use crate::{
-    error::ReviseResult,
+    error::{ReviseError, ReviseResult},
     git::{GitUtils, repo::GitRepository},
     hook::HookType,
 };
 
+pub mod command;
+pub mod merge;
+pub mod overrides;
+pub mod preset;
+pub mod scope;
+pub mod workspace;
+
+/// Where a config value comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    Preset,
+    /// A file named by `extends`.
+    Extends,
+    System,
+    User,
+    Repo,
+    Local,
+    Env,
+    /// A `[branch."<glob>"]` section.
+    Branch,
+    /// A `[path."<glob>"]` section.
+    Path,
+}
+
+impl Source {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Preset => "preset",
+            Self::Extends => "extends",
+            Self::System => "system",
+            Self::User => "user",
+            Self::Repo => "repo",
+            Self::Local => "local",
+            Self::Env => "env",
+            Self::Branch => "branch",
+            Self::Path => "path",
+        }
+    }
+}
+
+/// The values of one config file, or of the environment.
+#[derive(Debug, Clone)]
+pub struct Layer {
+    pub source: Source,
+    pub path: Option<PathBuf>,
+    pub values: Map<String, Value>,
+}
+
+impl Layer {
+    /// The file, or what else the values come from.
+    pub fn name(&self) -> String {
+        match (&self.path, self.source) {
+            (Some(path), _) => path.display().to_string(),
+            (None, Source::Env) => "REVISE_CONFIG__*".to_string(),
+            (None, Source::Preset) => "built-in".to_string(),
+            (None, source) => source.name().to_string(),
+        }
+    }
+}
+
+/// The built-in questions, as named in `prompts`.
+pub const BUILTIN_PROMPTS: [&str; 6] =
+    ["type", "scope", "subject", "body", "breaking", "issue"];
+
 pub static CFG: OnceLock<ReviseConfig> = OnceLock::new();
 
+/// The config with the override sections matched again once the files of
+/// the commit are staged, see [`apply_staged`].
+static STAGED: OnceLock<ReviseConfig> = OnceLock::new();
+
+/// The layers [`CFG`] was merged from.
+static LAYERS: OnceLock<Vec<Layer>> = OnceLock::new();
+
 pub fn initialize_config() -> ReviseResult<ReviseConfig> {
-    let config = CFG.get_or_init(|| {
-        dotenvy::dotenv().ok();
-        ReviseConfig::load_config().unwrap_or_else(|e| {
-            eprintln!("Load config err: {e}");
-            std::process::exit(exitcode::CONFIG);
-        })
-    });
-    Ok(config.clone())
+    if let Some(config) = CFG.get() {
+        return Ok(config.clone());
+    }
+    dotenvy::dotenv().ok();
+    let layers = ReviseConfig::layers()?;
+    let config = ReviseConfig::load_config(&layers)?;
+    LAYERS.get_or_init(|| layers);
+    Ok(CFG.get_or_init(|| config).clone())
 }
 
 pub fn get_config() -> &'static ReviseConfig {
-    CFG.get().unwrap()
+    STAGED.get().or_else(|| CFG.get()).unwrap()
+}
+
+/// Matches the `[path."<glob>"]` sections again, against the files staged
+/// now. The config is loaded before `-a`, `-p` or the staging prompt, when
+/// the files of the commit may not be staged yet. Only the first call has an
+/// effect.
+pub fn apply_staged() -> ReviseResult<()> {
+    let Some(layers) = LAYERS.get() else {
+        return Ok(());
+    };
+    let has_paths =
+        layers.iter().any(|layer| layer.values.contains_key("path"));
+    if has_paths && STAGED.get().is_none() {
+        let config = ReviseConfig::load_config(layers)?;
+        STAGED.get_or_init(|| config);
+    }
+    Ok(())
 }
 
-#[derive(Deserialize, Debug, Clone)]
+/// Every field is optional and missing ones keep their default, so a config
+/// can set only what it changes.
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+#[serde(default)]
+#[schemars(title = "revise.toml")]
 pub struct ReviseConfig {
+    /// Built-in preset the config starts from.
+    pub preset: Option<Preset>,
+    /// Another config file this one builds on, relative to this one.
+    pub extends: Option<String>,
+    /// Tera template the commit message is rendered with.
     pub template: String,
+    /// Questions to ask, in this order: `type`, `scope`, `subject`, `body`,
+    /// `breaking`, `issue` and the names of `fields`. All of them by
+    /// default, the fields last.
+    pub prompts: Vec<String>,
+    /// Custom questions, whose answers are template variables by their
+    /// name.
+    pub fields: Vec<Field>,
+    /// Commit types offered by the prompt, in this order.
     pub types: Vec<Type>,
+    /// Emoji for each commit type, available as `commit_icon`.
     pub emojis: Vec<Emoji>,
+    /// Scopes offered by the prompt.
     pub scopes: Vec<String>,
+    /// Scopes pre-selected for the staged files, by path.
+    pub scope_rules: Vec<ScopeRule>,
+    /// Also offer the workspace packages and top-level directories as
+    /// scopes, and pre-select the ones the staged files are in.
+    pub detect_scopes: bool,
     pub auto: Auto,
-    #[serde(default)]
+    /// API keys by name, e.g. `gemini_key`. `"{{env}}"` reads
+    /// `REVISE_<NAME>`.
     pub api_key: HashMap<String, String>,
-    #[serde(deserialize_with = "deserialize_hooks")]
+    /// Commands to run in each phase.
+    #[serde(
+        deserialize_with = "deserialize_hooks",
+        serialize_with = "serialize_hooks"
+    )]
+    #[schemars(with = "HookPhases")]
     pub hooks: HashMap<HookType, Vec<Hook>>,
-    #[serde(default)]
+    /// Files left out of the diff, as globs.
     pub exclude_files: Vec<String>,
+    /// Files never sent to the AI, as globs.
+    pub ai_exclude: Vec<String>,
+    pub secret_scan: SecretScan,
+    pub lint: Lint,
+    /// Settings for branches matching a glob, e.g. `[branch."release/*"]`.
+    #[schemars(with = "BTreeMap<String, ReviseConfig>")]
+    pub branch: BTreeMap<String, Map<String, Value>>,
+    /// Settings for commits that stage a file matching a glob, e.g.
+    /// `[path."docs/**"]`.
+    #[schemars(with = "BTreeMap<String, ReviseConfig>")]
+    pub path: BTreeMap<String, Map<String, Value>>,
 }
 
 #[derive(Deserialize, Debug, Clone, Default)]
 pub struct Render {}
 
-#[derive(Deserialize, Debug, Clone, Default)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, Default)]
 pub struct Emoji {
     pub key: String,
     pub value: String,
 }
 
-#[derive(Deserialize, Debug, Clone, Default)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, Default)]
 pub struct Type {
     pub key: String,
     pub value: String,
 }
 
-#[derive(Deserialize, Debug, Clone, Default)]
+#[derive(
+    Deserialize, Serialize, JsonSchema, Debug, Clone, Default, PartialEq,
+)]
+pub struct Field {
+    /// Template variable the answer is available as.
+    pub name: String,
+    #[serde(default)]
+    pub kind: FieldKind,
+    /// Question to ask, the name by default.
+    pub message: Option<String>,
+    /// A text answer can't be left empty.
+    #[serde(default)]
+    pub required: bool,
+    /// Regular expression a text answer has to match, e.g. `'^PROJ-\d+$'`.
+    pub validate: Option<String>,
+    /// Choices of a select field.
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+#[derive(
+    Deserialize,
+    Serialize,
+    JsonSchema,
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldKind {
+    /// Free text, a string.
+    #[default]
+    Text,
+    /// One of `options`, a string.
+    Select,
+    /// Yes or no, a boolean.
+    Confirm,
+}
+
+#[derive(
+    Deserialize, Serialize, JsonSchema, Debug, Clone, Default, PartialEq,
+)]
+pub struct ScopeRule {
+    /// Scope of the matching files.
+    pub scope: String,
+    /// Globs of repository-relative paths, e.g. `crates/api/**`.
+    pub files: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, Default)]
+#[serde(default)]
 pub struct Auto {
     pub git: AutoGit,
     pub commit: AutoCommit,
 }
 
-#[derive(Deserialize, Debug, Clone, Default)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, Default)]
 pub struct Hook {
+    /// Name to refer to the hook by, e.g. with `--skip-hook`.
+    pub name: Option<String>,
+    /// Shell command, run with `sh -c`.
     pub command: String,
+    /// Hooks run in ascending order.
     pub order: Option<u32>,
     pub skip: Option<bool>,
+    /// Seconds after which the command is killed.
+    pub timeout: Option<u64>,
+    /// Hooks with the same `order` and group run concurrently.
+    pub parallel_group: Option<String>,
+    #[serde(default)]
+    pub on_failure: OnFailure,
+    /// Extra environment variables.
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Directory to run in, relative to the repository root.
+    pub cwd: Option<PathBuf>,
+    /// Only run when a staged file matches one of these globs.
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Staged files to leave out, even if they match `files`.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Only run on branches matching one of these globs.
+    #[serde(default)]
+    pub branches: Vec<String>,
+    /// Only run for these commit types.
+    #[serde(default)]
+    pub types: Vec<String>,
+    /// Append the matching staged files to the command.
+    #[serde(default)]
+    pub pass_files: bool,
+}
+
+// The schema of `[hooks]`, which is a map keyed by `HookType` in code
+/// Hooks by phase.
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct HookPhases {
+    pre_add: Option<Vec<Hook>>,
+    post_add: Option<Vec<Hook>>,
+    pre_commit: Option<Vec<Hook>>,
+    commit_msg: Option<Vec<Hook>>,
+    post_commit: Option<Vec<Hook>>,
+    pre_push: Option<Vec<Hook>>,
+    post_merge: Option<Vec<Hook>>,
+}
+
+/// What to do when a hook fails or times out.
+#[derive(
+    Deserialize,
+    Serialize,
+    JsonSchema,
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum OnFailure {
+    /// Stop the whole run.
+    #[default]
+    Abort,
+    /// Print a warning and carry on.
+    Warn,
+    /// Ask whether to carry on.
+    Prompt,
 }
 
 #[allow(clippy::struct_excessive_bools)]
-#[derive(Deserialize, Debug, Clone, Copy, Default)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, Copy, Default)]
+#[serde(default)]
 pub struct AutoGit {
     pub add: bool,
     pub push: bool,
@@ -79,12 +339,83 @@ pub struct AutoGit {
     pub footer: bool,
 }
 
-#[derive(Deserialize, Debug, Clone, Copy, Default)]
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, Copy, Default)]
+#[serde(default)]
 pub struct AutoCommit {
     pub content: bool,
     pub footer: bool,
 }
 
+/// Controls how the staged diff is scrubbed before it is sent to an AI
+/// provider.
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct SecretScan {
+    pub enabled: bool,
+    /// Refuse to send the diff at all when a secret is found.
+    pub block: bool,
+    /// Minimum Shannon entropy (bits per char) for a token to be treated as
+    /// a secret.
+    pub entropy_threshold: f64,
+}
+
+impl Default for SecretScan {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            block: false,
+            entropy_threshold: 4.5,
+        }
+    }
+}
+
+/// Rules used by `git-revise lint`.
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+#[serde(default)]
+pub struct Lint {
+    /// Longest subject, in characters.
+    pub subject_max_length: usize,
+    /// Longest body line, in characters.
+    pub body_max_line_length: usize,
+    /// Severity per rule name, overriding the built-in one.
+    pub rules: HashMap<String, Severity>,
+}
+
+impl Default for Lint {
+    fn default() -> Self {
+        Self {
+            subject_max_length: 72,
+            body_max_line_length: 100,
+            rules: HashMap::new(),
+        }
+    }
+}
+
+impl Lint {
+    pub fn severity(&self, rule: &str, default: Severity) -> Severity {
+        self.rules.get(rule).copied().unwrap_or(default)
+    }
+}
+
+#[derive(
+    Deserialize,
+    Serialize,
+    JsonSchema,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    Warning,
+    Error,
+}
+
 fn deserialize_hooks<'de, D>(
     deserializer: D,
 ) -> Result<HashMap<HookType, Vec<Hook>>, D::Error>
@@ -104,6 +435,17 @@ where
     Ok(hooks)
 }
 
+fn serialize_hooks<S>(
+    hooks: &HashMap<HookType, Vec<Hook>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer
+        .collect_map(hooks.iter().map(|(phase, hooks)| (phase.name(), hooks)))
+}
+
 impl ReviseConfig {
     pub fn new() -> Self {
         Self::default()
@@ -129,6 +471,18 @@ impl ReviseConfig {
         None
     }
 
+    /// The names of the questions to ask, in order.
+    pub fn prompt_order(&self) -> Vec<String> {
+        if !self.prompts.is_empty() {
+            return self.prompts.clone();
+        }
+        BUILTIN_PROMPTS
+            .iter()
+            .map(ToString::to_string)
+            .chain(self.fields.iter().map(|f| f.name.clone()))
+            .collect()
+    }
+
     pub fn get_emoji(&self, key: &str) -> Option<String> {
         self.emojis
             .iter()
@@ -136,151 +490,227 @@ impl ReviseConfig {
             .map(|e| e.value.clone())
     }
 
+    /// The `scopes`, followed by the scopes of the `scope_rules`.
     pub fn get_scopes(&self) -> Vec<String> {
-        self.scopes.clone()
+        let mut scopes = self.scopes.clone();
+        for rule in &self.scope_rules {
+            if !scopes.contains(&rule.scope) {
+                scopes.push(rule.scope.clone());
+            }
+        }
+        scopes
     }
 
-    pub fn get_config_path() -> ReviseResult<Option<PathBuf>> {
-        let mut config_paths = Vec::new();
+    /// The config files that are searched, from the lowest layer to the
+    /// highest, whether they exist or not.
+    pub fn config_locations() -> Vec<(Source, PathBuf)> {
+        let mut locations = Vec::new();
 
-        if let Ok(repo) = GitUtils::git_repo() {
-            config_paths.push(PathBuf::from(repo).join("revise.toml"));
+        if cfg!(windows) {
+            if let Some(dir) = std::env::var_os("PROGRAMDATA") {
+                locations.push((
+                    Source::System,
+                    PathBuf::from(dir).join("revise").join("revise.toml"),
+                ));
+            }
+        } else {
+            locations.push((
+                Source::System,
+                PathBuf::from("/etc/revise/revise.toml"),
+            ));
         }
 
         if let Some(config_dir) = dirs::config_local_dir() {
-            config_paths.push(config_dir.join("revise").join("revise.toml"));
+            locations.push((
+                Source::User,
+                config_dir.join("revise").join("revise.toml"),
+            ));
         }
 
-        let config_path = config_paths
-            .into_iter()
-            .find(|path| path.try_exists().unwrap_or(false));
+        if let Ok(repo) = GitUtils::new().repo_root() {
+            let repo = PathBuf::from(repo);
+            locations.push((Source::Repo, repo.join("revise.toml")));
+            // a linked worktree, which has a `.git` file, shares the local
+            // file of the main checkout unless it has its own
+            let mut local = repo.join(".revise.local.toml");
+            if !local.exists() && repo.join(".git").is_file() {
+                if let Ok(Some(main)) = GitUtils::git_main_worktree() {
+                    local = main.join(".revise.local.toml");
+                }
+            }
+            locations.push((Source::Local, local));
+        }
 
-        Ok(config_path)
+        locations
     }
 
-    pub fn load_config() -> ReviseResult<Self> {
-        let config_path = Self::get_config_path()?;
-        let config = match config_path {
-            Some(path) => {
-                return Realme::builder()
-                    .load(Adaptor::new(EnvSource::<EnvParser>::new("REVISE_")))
-                    .load(Adaptor::new(FileSource::<TomlParser>::new(path)))
-                    .build()?
-                    .try_deserialize()
-                    .map_err(|e| anyhow::anyhow!(e.to_string()));
+    /// Every config file that exists, in merge order, each preceded by the
+    /// files it `extends`, and the `REVISE_CONFIG__*` environment variables
+    /// last. The selected `preset` comes first.
+    pub fn layers() -> ReviseResult<Vec<Layer>> {
+        let mut layers = Vec::new();
+        for (source, path) in Self::config_locations() {
+            if path.try_exists().unwrap_or(false) {
+                Self::read_extended(
+                    source,
+                    path,
+                    &mut layers,
+                    &mut Vec::new(),
+                )?;
             }
-            None => Self::default(),
-        };
+        }
+        layers.push(Layer {
+            source: Source::Env,
+            path: None,
+            values: merge::env_layer(std::env::vars()),
+        });
 
-        let msg = format!(
-            "{}",
-            "Read config file failed, loading default config!!!!!"
-                .red()
-                .on_black()
-        );
-        println!("{msg}");
-        Ok(config)
+        let preset = layers
+            .iter()
+            .rev()
+            .find_map(|layer| layer.values.get("preset"));
+        if let Some(preset) = preset {
+            let preset = Preset::deserialize(preset)
+                .map_err(|e| ReviseError::Config(format!("preset: {e}")))?;
+            layers.insert(
+                0,
+                Layer {
+                    source: Source::Preset,
+                    path: None,
+                    values: preset.layer(),
+                },
+            );
+        }
+        Ok(layers)
+    }
+
+    /// Reads `path` into `layers`, after the file it `extends`. `chain` holds
+    /// the files that extend it, to catch loops.
+    fn read_extended(
+        source: Source,
+        path: PathBuf,
+        layers: &mut Vec<Layer>,
+        chain: &mut Vec<PathBuf>,
+    ) -> ReviseResult<()> {
+        let values = Self::read_layer(&path)?;
+        if let Some(extends) = values.get("extends") {
+            let Some(base) = extends.as_str() else {
+                return Err(ReviseError::Config(format!(
+                    "{}: `extends` must be a path",
+                    path.display()
+                ))
+                .into());
+            };
+            let base = match base.strip_prefix("~/") {
+                Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+                None => path.parent().unwrap_or(Path::new("")).join(base),
+            };
+            if !base.is_file() {
+                return Err(ReviseError::Config(format!(
+                    "{}: `extends` names {}, which doesn't exist",
+                    path.display(),
+                    base.display()
+                ))
+                .into());
+            }
+            chain.push(path.canonicalize()?);
+            if chain.contains(&base.canonicalize()?) {
+                return Err(ReviseError::Config(format!(
+                    "{}: `extends` loops back to {}",
+                    path.display(),
+                    base.display()
+                ))
+                .into());
+            }
+            Self::read_extended(Source::Extends, base, layers, chain)?;
+            chain.pop();
+        }
+        layers.push(Layer {
+            source,
+            path: Some(path),
+            values,
+        });
+        Ok(())
+    }
+
+    /// Merges the [`layers`](Self::layers) with [`merge::merge_layer`], then
+    /// the override sections for the current branch and staged files.
+    pub fn load_config(layers: &[Layer]) -> ReviseResult<Self> {
+        let mut config = Map::new();
+        for layer in layers {
+            merge::merge_layer(&mut config, layer.values.clone());
+        }
+        let sections = overrides::current(&config)?;
+        for section in &sections {
+            merge::merge_layer(&mut config, section.values.clone());
+        }
+        serde_json::from_value(Value::Object(config)).map_err(|e| {
+            // point at the layer or section that can't be read on its own
+            let culprit = layers
+                .iter()
+                .map(|layer| (layer.name(), &layer.values))
+                .chain(sections.iter().map(|s| (s.name(), &s.values)))
+                .find(|(_, values)| {
+                    serde_json::from_value::<Self>(Value::Object(
+                        (*values).clone(),
+                    ))
+                    .is_err()
+                });
+            let msg = match culprit {
+                Some((name, _)) => format!("{name}: {e}"),
+                None => e.to_string(),
+            };
+            ReviseError::Config(msg).into()
+        })
+    }
+
+    /// Reads one config file, with `"{{env}}"` values taken from the
+    /// environment.
+    pub fn read_layer(path: &Path) -> ReviseResult<Map<String, Value>> {
+        Self::read_file(path).map_err(|e| {
+            ReviseError::Config(format!("{}: {e}", path.display())).into()
+        })
+    }
+
+    /// [`read_layer`](Self::read_layer) with the parser's error.
+    fn read_file(path: &Path) -> realme::RealmeResult<Map<String, Value>> {
+        let layer: Value = Realme::builder()
+            // parsed before the file, so its `{{env}}` values can be filled
+            .load(
+                Adaptor::new(EnvSource::<EnvParser>::new("REVISE_"))
+                    .priority(1),
+            )
+            .load(Adaptor::new(FileSource::<TomlParser>::new(path)))
+            .build()
+            .and_then(|realme| realme.try_deserialize())?;
+        let Value::Object(mut layer) = layer else {
+            return Ok(Map::new());
+        };
+        // realme also puts every `REVISE_*` variable at the top level, those
+        // are not config keys
+        for (name, value) in std::env::vars() {
+            if let Some(key) = name.strip_prefix("REVISE_") {
+                let key = key.to_lowercase();
+                if layer.get(&key) == Some(&Value::String(value)) {
+                    layer.remove(&key);
+                }
+            }
+        }
+        Ok(layer)
     }
 }
 
-#[allow(clippy::too_many_lines)]
 impl Default for ReviseConfig {
     fn default() -> Self {
+        let base = Preset::Conventional.base();
         Self {
-            types: vec![
-                Type {
-                    key: "feat".to_owned(),
-                    value: "A new feature".to_owned(),
-                },
-                Type {
-                    key: "fix".to_owned(),
-                    value: "A bug fix".to_owned(),
-                },
-                Type {
-                    key: "docs".to_owned(),
-                    value: "Documentation only changes".to_owned(),
-                },
-                Type {
-                    key: "style".to_owned(),
-                    value: "Changes that do not affect the meaning of the code".to_owned(),
-                },
-                Type {
-                    key: "refactor".to_owned(),
-                    value: "A code change that neither fixes a bug nor adds a feature".to_owned(),
-                },
-                Type {
-                    key: "perf".to_owned(),
-                    value: "A code change that improves performance".to_owned(),
-                },
-                Type {
-                    key: "test".to_owned(),
-                    value: "Adding missing tests or correcting existing tests".to_owned(),
-                },
-                Type {
-                    key: "build".to_owned(),
-                    value: "Changes that affect the build system or external dependencies"
-                        .to_owned(),
-                },
-                Type {
-                    key: "ci".to_owned(),
-                    value: "Changes to our CI configuration files and scripts".to_owned(),
-                },
-                Type {
-                    key: "chore".to_owned(),
-                    value: "Other changes that don\"t modify src or test files".to_owned(),
-                },
-                Type {
-                    key: "revert".to_owned(),
-                    value: "Reverts a previous commit".to_owned(),
-                },
-            ],
-            emojis: vec![
-                Emoji {
-                    key: "feat".to_owned(),
-                    value: "✨".to_owned(),
-                },
-                Emoji {
-                    key: "fix".to_owned(),
-                    value: "🐛".to_owned(),
-                },
-                Emoji {
-                    key: "docs".to_owned(),
-                    value: "📚".to_owned(),
-                },
-                Emoji {
-                    key: "style".to_owned(),
-                    value: "🎨".to_owned(),
-                },
-                Emoji {
-                    key: "refactor".to_owned(),
-                    value: "♻️".to_owned(),
-                },
-                Emoji {
-                    key: "perf".to_owned(),
-                    value: "⚡️".to_owned(),
-                },
-                Emoji {
-                    key: "test".to_owned(),
-                    value: "✅".to_owned(),
-                },
-                Emoji {
-                    key: "build".to_owned(),
-                    value: "📦️".to_owned(),
-                },
-                Emoji {
-                    key: "ci".to_owned(),
-                    value: "⚙️".to_owned(),
-                },
-                Emoji {
-                    key: "chore".to_owned(),
-                    value: "🔨".to_owned(),
-                },
-                Emoji {
-                    key: "revert".to_owned(),
-                    value: "🔙".to_owned(),
-                },
-            ],
+            preset: None,
+            extends: None,
+            types: base.types,
+            emojis: base.emojis,
             scopes: Vec::new(),
+            scope_rules: Vec::new(),
+            detect_scopes: false,
             auto: Auto {
                 git: AutoGit::default(),
                 commit: AutoCommit::default(),
@@ -288,10 +718,37 @@ impl Default for ReviseConfig {
             api_key: HashMap::new(),
             hooks: HashMap::new(),
             exclude_files: Vec::new(),
-            template: String::from("
-{{commit_icon}} {{ commit_type }}{% if commit_scope %}({{commit_scope}}){% endif %}{% if commit_breaking %}!{% endif %}: {{ commit_subject }}{% if commit_issue %}({{commit_issue}}){% endif %}   
-{% if commit_body %}\n{{ commit_body }}{% endif %}
-{% if commit_breaking %}\nBREAKING CHANGE: {{ commit_breaking }}{% endif %}"),
+            ai_exclude: Vec::new(),
+            secret_scan: SecretScan::default(),
+            lint: Lint::default(),
+            branch: BTreeMap::new(),
+            path: BTreeMap::new(),
+            template: base.template,
+            prompts: Vec::new(),
+            fields: Vec::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_partial_config() {
+        let cfg: ReviseConfig = serde_json::from_value(json!({
+            "scopes": ["api"],
+            "auto": { "git": { "push": true } },
+            "hooks": { "post-commit": [{ "command": "true" }] },
+        }))
+        .unwrap();
+        assert_eq!(cfg.scopes, vec!["api".to_string()]);
+        assert!(cfg.auto.git.push && !cfg.auto.git.add);
+        assert_eq!(cfg.types.len(), ReviseConfig::default().types.len());
+        assert!(cfg.template.contains("commit_subject"));
+        assert!(!cfg.hooks.contains_key(&HookType::PreCommit));
+        assert_eq!(cfg.hooks[&HookType::PostCommit].len(), 1);
+    }
+}
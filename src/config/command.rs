@@ -0,0 +1,875 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    io::{self, IsTerminal},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use colored::Colorize;
+use inquire::Select;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use tera::{Context, Tera};
+
+use super::{
+    BUILTIN_PROMPTS, Field, FieldKind, Hook, Layer, ReviseConfig, ScopeRule,
+    Severity, Source, merge, overrides, preset::Preset, workspace,
+};
+use crate::{
+    cli::ConfigCommand,
+    error::{ReviseError, ReviseResult},
+    git::{GitUtils, pathspec::PathMatcher},
+    hook::HookType,
+    revise::lint,
+};
+
+/// Where the published schema lives, for the `#:schema` hint of new configs.
+const SCHEMA_URL: &str = "https://raw.githubusercontent.com/vainjoker/revise/main/revise.schema.json";
+
+const STARTER: &str = r#"#:schema {schema}
+
+# Config for git-revise. Every setting is optional, the commented ones show
+# the defaults. Run `git revise config show` to see the effective config.
+
+# Types, emojis and template to start from: conventional, angular, gitmoji
+# or kernel. A shared base config can be added with
+# extends = "path/to/team.toml"
+preset = "{preset}"
+
+# Commit types offered by the prompt, in this order, replacing the preset's
+# types = [
+#     { key = "feat", value = "A new feature" },
+#     { key = "fix", value = "A bug fix" },
+# ]
+
+# Questions to ask, in this order, with custom ones from [[fields]]
+# prompts = ["type", "scope", "subject", "body", "breaking", "issue"]
+# fields = [
+#     { name = "ticket", kind = "text", required = true, validate = '^PROJ-\d+$' },
+# ]
+# and `{{ ticket }}` in the template
+
+# Scopes offered by the prompt
+{scopes}
+# Also offer the workspace packages and top-level directories as scopes,
+# and pre-select the ones the staged files are in
+# detect_scopes = false
+
+# Scopes pre-selected for the staged files, by path
+# scope_rules = [
+#     { scope = "api", files = ["crates/api/**"] },
+# ]
+
+# Files left out of the diff that is sent to the AI
+# exclude_files = ["CHANGELOG.md"]
+
+# Read from $REVISE_GEMINI_KEY
+# api_key.gemini_key = "{{env}}"
+
+[auto.git]
+# add = false     # stage every change before committing
+# push = false    # push after committing
+
+[lint]
+# subject_max_length = 72
+# body_max_line_length = 100
+# rules = { scope-enum = "error" }
+
+[hooks]
+# pre-commit = [
+#     { name = "fmt", command = "cargo fmt --check", files = ["*.rs"] },
+# ]
+"#;
+
+/// Where each value was set, keyed by its dotted path.
+type Sources = BTreeMap<String, Vec<Source>>;
+
+/// Something `config validate` found.
+struct Finding {
+    location: String,
+    severity: Severity,
+    message: String,
+}
+
+pub fn run(command: &ConfigCommand) -> ReviseResult<()> {
+    match command {
+        ConfigCommand::Init { force, preset } => init(*force, *preset),
+        ConfigCommand::Show => show(),
+        ConfigCommand::Validate => validate(),
+        ConfigCommand::Path => {
+            paths();
+            Ok(())
+        }
+        ConfigCommand::Schema => {
+            println!("{}", schema()?);
+            Ok(())
+        }
+    }
+}
+
+/// Writes a commented starter `revise.toml` at the repository root, with
+/// the packages of the workspace as scopes.
+pub fn init(force: bool, preset: Option<Preset>) -> ReviseResult<()> {
+    let root = match GitUtils::new().repo_root() {
+        Ok(root) => PathBuf::from(root),
+        Err(_) => env::current_dir()?,
+    };
+    let path = root.join("revise.toml");
+    if path.exists() && !force {
+        return Err(ReviseError::Validation(format!(
+            "{} already exists, pass --force to overwrite it",
+            path.display()
+        ))
+        .into());
+    }
+    let preset = match preset {
+        Some(preset) => preset,
+        None if io::stdin().is_terminal() => Select::new(
+            "Which preset do you want to start from?",
+            Preset::ALL.to_vec(),
+        )
+        .with_formatter(&|preset| preset.value.name().to_string())
+        .prompt()?,
+        None => Preset::Conventional,
+    };
+    fs::write(&path, starter(preset, &workspace::detect_scopes(&root)))?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
+fn starter(preset: Preset, scopes: &[String]) -> String {
+    let scopes = if scopes.is_empty() {
+        "# scopes = [\"api\", \"cli\"]\n".to_string()
+    } else {
+        let items: Vec<String> = scopes
+            .iter()
+            .map(|s| format!("    {},\n", json!(s)))
+            .collect();
+        format!("scopes = [\n{}]\n", items.concat())
+    };
+    STARTER
+        .replace("{schema}", SCHEMA_URL)
+        .replace("{preset}", preset.name())
+        .replace("{scopes}", &scopes)
+}
+
+/// Prints the effective config as TOML, each value with the layers it
+/// comes from.
+pub fn show() -> ReviseResult<()> {
+    let layers = ReviseConfig::layers()?;
+    let mut merged = Map::new();
+    let mut sources = Sources::new();
+    for layer in &layers {
+        merge::merge_layer(&mut merged, layer.values.clone());
+        let appended = merge::appended_lists(&merged);
+        record(&mut sources, "", &layer.values, layer.source, &appended);
+    }
+    let sections = overrides::current(&merged)?;
+    for section in &sections {
+        merge::merge_layer(&mut merged, section.values.clone());
+        let appended = merge::appended_lists(&merged);
+        record(&mut sources, "", &section.values, section.source, &appended);
+    }
+    let config: ReviseConfig = serde_json::from_value(Value::Object(merged))
+        .map_err(|e| ReviseError::Config(e.to_string()))?;
+    let Value::Object(mut effective) = serde_json::to_value(&config)? else {
+        return Ok(());
+    };
+    prune_hooks(&mut effective)?;
+
+    println!("# layers, lowest first:");
+    for layer in &layers {
+        if layer.path.is_some() || !layer.values.is_empty() {
+            println!("#   {:<6} {}", layer.source.name(), layer.name());
+        }
+    }
+    for section in &sections {
+        println!("#   {:<6} {}", section.source.name(), section.name());
+    }
+    println!();
+    print_table("", &effective, &sources);
+    Ok(())
+}
+
+/// The JSON Schema of [`ReviseConfig`], as published in
+/// `revise.schema.json`.
+pub fn schema() -> ReviseResult<String> {
+    Ok(serde_json::to_string_pretty(&schemars::schema_for!(
+        ReviseConfig
+    ))?)
+}
+
+/// Lists the config files that are searched, in merge order.
+pub fn paths() {
+    for (source, path) in ReviseConfig::config_locations() {
+        let found = if path.try_exists().unwrap_or(false) {
+            String::new()
+        } else {
+            format!(" {}", "(not found)".dimmed())
+        };
+        println!("{:<6} {}{found}", source.name(), path.display());
+    }
+    println!(
+        "{:<6} REVISE_CONFIG__* environment variables",
+        Source::Env.name()
+    );
+}
+
+/// Checks every config file and the merged config, printing what is wrong.
+pub fn validate() -> ReviseResult<()> {
+    let mut findings = Vec::new();
+    let layers = ReviseConfig::layers().unwrap_or_else(|e| {
+        let message = match e.downcast_ref::<ReviseError>() {
+            Some(ReviseError::Config(msg)) => msg.clone(),
+            _ => e.to_string(),
+        };
+        findings.push(Finding {
+            location: "config".to_string(),
+            severity: Severity::Error,
+            message,
+        });
+        Vec::new()
+    });
+    let files: Vec<&Layer> =
+        layers.iter().filter(|layer| layer.path.is_some()).collect();
+    for layer in &files {
+        check_layer(layer, &mut findings);
+    }
+
+    let mut merged = Map::new();
+    for layer in &layers {
+        merge::merge_layer(&mut merged, layer.values.clone());
+    }
+    if let Ok(config) = serde_json::from_value(Value::Object(merged)) {
+        check_config(&config, &mut findings);
+    }
+
+    for finding in &findings {
+        let severity = match finding.severity {
+            Severity::Error => "error".red().bold(),
+            _ => "warning".yellow().bold(),
+        };
+        println!("{}: {severity}: {}", finding.location, finding.message);
+    }
+    let errors = findings
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        return Err(ReviseError::Validation(format!(
+            "The config has {errors} error(s)"
+        ))
+        .into());
+    }
+    println!(
+        "{} config file(s) checked, {} warning(s)",
+        files.len(),
+        findings.len()
+    );
+    Ok(())
+}
+
+/// Checks one file on its own: unknown keys, values of the wrong type,
+/// hook phases, merge strategies and override sections.
+fn check_layer(layer: &Layer, findings: &mut Vec<Finding>) {
+    check_values(&layer.name(), &layer.values, findings);
+
+    for section in overrides::sections(&layer.values) {
+        let location = format!("{} {}", layer.name(), section.name());
+        let mut add = |severity, message| {
+            findings.push(Finding {
+                location: location.clone(),
+                severity,
+                message,
+            });
+        };
+        if let Err(e) = section.applies(None, &[]) {
+            add(Severity::Error, e.to_string());
+        }
+        for key in ["preset", "extends", "branch", "path"] {
+            if section.values.contains_key(key) {
+                add(
+                    Severity::Warning,
+                    format!("`{key}` has no effect in a section"),
+                );
+            }
+        }
+        check_values(&location, &section.values, findings);
+    }
+}
+
+fn check_values(
+    location: &str,
+    values: &Map<String, Value>,
+    findings: &mut Vec<Finding>,
+) {
+    let mut add = |severity, message| {
+        findings.push(Finding {
+            location: location.to_string(),
+            severity,
+            message,
+        });
+    };
+
+    let mut unknown = Vec::new();
+    unknown_keys(values, &shape(), "", &mut unknown);
+    // unknown hook phases are reported below
+    unknown.retain(|key| {
+        !key.starts_with("hooks.") || key.matches('.').count() > 1
+    });
+    for key in unknown {
+        add(Severity::Warning, format!("unknown key `{key}`"));
+    }
+
+    for (key, value) in values {
+        let single = json!({ key: value });
+        if let Err(e) = serde_json::from_value::<ReviseConfig>(single) {
+            add(Severity::Error, format!("`{key}`: {e}"));
+        }
+    }
+
+    if let Some(Value::Object(phases)) = values.get("hooks") {
+        for phase in phases.keys() {
+            if HookType::from_str(phase).is_err() {
+                let names: Vec<&str> =
+                    HookType::ALL.iter().map(HookType::name).collect();
+                add(
+                    Severity::Error,
+                    format!(
+                        "unknown hook phase `{phase}`, expected one of: {}",
+                        names.join(", ")
+                    ),
+                );
+            }
+        }
+    }
+
+    if let Some(Value::Object(strategies)) = values.get("merge") {
+        for (list, strategy) in strategies {
+            if !merge::LISTS.iter().any(|(name, _)| name == list) {
+                add(Severity::Warning, format!("`merge.{list}` has no effect"));
+                continue;
+            }
+            if merge::Strategy::deserialize(strategy).is_err() {
+                add(
+                    Severity::Error,
+                    format!("`merge.{list}` must be \"append\" or \"replace\""),
+                );
+            }
+        }
+    }
+}
+
+/// Checks the merged config: types against emojis, the template, lint rule
+/// names and hooks.
+fn check_config(config: &ReviseConfig, findings: &mut Vec<Finding>) {
+    let mut add = |severity, message| {
+        findings.push(Finding {
+            location: "config".to_string(),
+            severity,
+            message,
+        });
+    };
+
+    for (idx, t) in config.types.iter().enumerate() {
+        if config.types[..idx].iter().any(|other| other.key == t.key) {
+            add(
+                Severity::Error,
+                format!("type `{}` is defined twice", t.key),
+            );
+        }
+        if config.template.contains("commit_icon")
+            && config.get_emoji(&t.key).is_none()
+        {
+            add(Severity::Warning, format!("type `{}` has no emoji", t.key));
+        }
+    }
+    for emoji in &config.emojis {
+        if !config.types.iter().any(|t| t.key == emoji.key) {
+            add(
+                Severity::Warning,
+                format!("emoji for `{}`, which is not a type", emoji.key),
+            );
+        }
+    }
+
+    if let Err(e) = render_sample(&config.template, &config.fields) {
+        let cause = std::error::Error::source(&e)
+            .map_or_else(String::new, |cause| format!(": {cause}"));
+        add(Severity::Error, format!("template: {e}{cause}"));
+    }
+
+    for rule in config.lint.rules.keys() {
+        if !lint::RULES.contains(&rule.as_str()) {
+            add(Severity::Warning, format!("unknown lint rule `{rule}`"));
+        }
+    }
+
+    for rule in &config.scope_rules {
+        if rule.scope.trim().is_empty() {
+            add(Severity::Error, "a scope rule has no scope".to_string());
+        }
+        if let Err(e) = PathMatcher::new(&rule.files) {
+            add(Severity::Error, format!("scope `{}`: {e}", rule.scope));
+        }
+    }
+
+    let mut names = Vec::new();
+    for (phase, hooks) in &config.hooks {
+        for hook in hooks {
+            if hook.command.trim().is_empty() {
+                add(
+                    Severity::Error,
+                    format!("a `{}` hook has no command", phase.name()),
+                );
+            }
+            if !hook.types.is_empty() && !phase.knows_type() {
+                add(
+                    Severity::Error,
+                    format!(
+                        "`{}` hooks run before there is a commit type, they \
+                         can't have `types`",
+                        phase.name()
+                    ),
+                );
+            }
+            if let Some(name) = &hook.name {
+                if names.contains(&name) {
+                    add(
+                        Severity::Warning,
+                        format!("hook name `{name}` is used more than once"),
+                    );
+                }
+                names.push(name);
+            }
+        }
+    }
+
+    check_prompts(config, findings);
+}
+
+/// Checks `prompts` and `fields`: names, select options and the patterns
+/// answers are validated with.
+fn check_prompts(config: &ReviseConfig, findings: &mut Vec<Finding>) {
+    let mut add = |severity, message| {
+        findings.push(Finding {
+            location: "config".to_string(),
+            severity,
+            message,
+        });
+    };
+
+    let identifier = Regex::new("^[A-Za-z_][A-Za-z0-9_]*$").unwrap();
+    for (idx, field) in config.fields.iter().enumerate() {
+        let name = &field.name;
+        if !identifier.is_match(name) {
+            add(
+                Severity::Error,
+                format!("field name `{name}` is not a template variable name"),
+            );
+        }
+        if name.starts_with("commit_") || BUILTIN_PROMPTS.contains(&&**name) {
+            add(
+                Severity::Error,
+                format!("field name `{name}` is taken by a built-in"),
+            );
+        }
+        if config.fields[..idx].iter().any(|other| &other.name == name) {
+            add(Severity::Error, format!("field `{name}` is defined twice"));
+        }
+        if field.kind == FieldKind::Select && field.options.is_empty() {
+            add(
+                Severity::Error,
+                format!("select field `{name}` has no options"),
+            );
+        }
+        if let Some(Err(e)) = field.validate.as_deref().map(Regex::new) {
+            add(Severity::Error, format!("field `{name}`: {e}"));
+        }
+        if !config.prompts.is_empty() && !config.prompts.contains(name) {
+            add(
+                Severity::Warning,
+                format!("field `{name}` is not in `prompts`, so never asked"),
+            );
+        }
+    }
+
+    for (idx, prompt) in config.prompts.iter().enumerate() {
+        if !BUILTIN_PROMPTS.contains(&prompt.as_str())
+            && !config.fields.iter().any(|f| &f.name == prompt)
+        {
+            add(
+                Severity::Error,
+                format!("prompt `{prompt}` is neither built in nor a field"),
+            );
+        }
+        if config.prompts[..idx].contains(prompt) {
+            add(
+                Severity::Warning,
+                format!("prompt `{prompt}` is asked twice"),
+            );
+        }
+    }
+    if !config.prompt_order().iter().any(|p| p == "subject") {
+        add(
+            Severity::Warning,
+            "`prompts` leaves out the subject, only the AI writes one"
+                .to_string(),
+        );
+    }
+}
+
+/// Renders the template with every variable set, the custom fields
+/// included.
+fn render_sample(
+    template: &str,
+    fields: &[Field],
+) -> Result<String, tera::Error> {
+    let mut tera = Tera::default();
+    tera.add_raw_template("template", template)?;
+    let mut ctx = Context::new();
+    for (name, value) in [
+        ("commit_type", "feat"),
+        ("commit_icon", "✨"),
+        ("commit_scope", "api"),
+        ("commit_subject", "add users"),
+        ("commit_body", "body"),
+        ("commit_breaking", "BREAKING CHANGE: users"),
+        ("commit_breaking_symbol", "!"),
+        ("commit_issue", "#1"),
+    ] {
+        ctx.insert(name, value);
+    }
+    for field in fields {
+        let value = match field.kind {
+            FieldKind::Text => json!("PROJ-1"),
+            FieldKind::Select => json!(field.options.first()),
+            FieldKind::Confirm => json!(true),
+        };
+        ctx.insert(&field.name, &value);
+    }
+    tera.render("template", &ctx)
+}
+
+/// The keys a config may have: the default config, with the shape of a hook
+/// under every phase.
+fn shape() -> Map<String, Value> {
+    let mut shape = match serde_json::to_value(ReviseConfig::default()) {
+        Ok(Value::Object(shape)) => shape,
+        _ => Map::new(),
+    };
+    let hook = serde_json::to_value(Hook::default()).unwrap_or_default();
+    let phases: Map<String, Value> = HookType::ALL
+        .iter()
+        .map(|phase| (phase.name().to_string(), json!([hook])))
+        .collect();
+    shape.insert("hooks".to_string(), Value::Object(phases));
+    shape.insert("merge".to_string(), Value::Object(Map::new()));
+    shape.insert("scope_rules".to_string(), json!([ScopeRule::default()]));
+    shape.insert("fields".to_string(), json!([Field::default()]));
+    shape
+}
+
+fn unknown_keys(
+    raw: &Map<String, Value>,
+    shape: &Map<String, Value>,
+    prefix: &str,
+    unknown: &mut Vec<String>,
+) {
+    // free-form tables like `api_key`
+    if shape.is_empty() {
+        return;
+    }
+    for (key, value) in raw {
+        let path = key_path(prefix, key);
+        match (shape.get(key), value) {
+            (None, _) => unknown.push(path),
+            (Some(Value::Object(shape)), Value::Object(raw)) => {
+                unknown_keys(raw, shape, &path, unknown);
+            }
+            (Some(Value::Array(items)), Value::Array(raw)) => {
+                let Some(Value::Object(shape)) = items.first() else {
+                    continue;
+                };
+                for item in raw {
+                    if let Value::Object(item) = item {
+                        unknown_keys(item, shape, &path, unknown);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn record(
+    sources: &mut Sources,
+    prefix: &str,
+    values: &Map<String, Value>,
+    source: Source,
+    appended: &[&str],
+) {
+    for (key, value) in values {
+        let path = key_path(prefix, key);
+        if let Value::Object(table) = value {
+            record(sources, &path, table, source, appended);
+            continue;
+        }
+        let top = if prefix.is_empty() { key } else { prefix };
+        let top = top.split('.').next().unwrap_or_default();
+        let entry = sources.entry(path).or_default();
+        if value.is_array() && appended.contains(&top) {
+            if !entry.contains(&source) {
+                entry.push(source);
+            }
+        } else {
+            *entry = vec![source];
+        }
+    }
+}
+
+/// Leaves out the hook fields that are left at their default.
+fn prune_hooks(config: &mut Map<String, Value>) -> ReviseResult<()> {
+    let default = serde_json::to_value(Hook::default())?;
+    let Some(Value::Object(phases)) = config.get_mut("hooks") else {
+        return Ok(());
+    };
+    for hook in phases
+        .values_mut()
+        .filter_map(Value::as_array_mut)
+        .flatten()
+    {
+        if let Value::Object(fields) = hook {
+            fields.retain(|k, v| k == "command" || default.get(k) != Some(v));
+        }
+    }
+    Ok(())
+}
+
+fn print_table(prefix: &str, table: &Map<String, Value>, sources: &Sources) {
+    for (key, value) in table {
+        let path = key_path(prefix, key);
+        let source = source_of(sources, &path);
+        match value {
+            Value::Null => {}
+            Value::Object(inner) if !inner.is_empty() => {
+                print_table(&path, inner, sources);
+            }
+            Value::String(_) if prefix == "api_key" => {
+                println!("{path} = \"***\"  # {source}");
+            }
+            Value::Array(items) if items.iter().any(Value::is_object) => {
+                println!("{path} = [  # {source}");
+                for item in items {
+                    println!("    {},", inline(item));
+                }
+                println!("]");
+            }
+            _ => println!("{path} = {}  # {source}", inline(value)),
+        }
+    }
+}
+
+/// The layers that set `path` or the closest table around it.
+fn source_of(sources: &Sources, path: &str) -> String {
+    let mut path = path;
+    loop {
+        if let Some(found) = sources.get(path) {
+            let names: Vec<&str> = found.iter().map(|s| s.name()).collect();
+            return names.join(" + ");
+        }
+        match path.rsplit_once('.') {
+            Some((parent, _)) => path = parent,
+            None => return Source::Default.name().to_string(),
+        }
+    }
+}
+
+/// A value as inline TOML; JSON scalars are valid TOML as they are.
+fn inline(value: &Value) -> String {
+    match value {
+        Value::Array(items) => {
+            let items: Vec<String> =
+                items.iter().filter(|v| !v.is_null()).map(inline).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Object(table) if table.is_empty() => "{}".to_string(),
+        Value::Object(table) => {
+            let fields: Vec<String> = table
+                .iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| format!("{} = {}", toml_key(k), inline(v)))
+                .collect();
+            format!("{{ {} }}", fields.join(", "))
+        }
+        _ => value.to_string(),
+    }
+}
+
+fn key_path(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        toml_key(key)
+    } else {
+        format!("{prefix}.{}", toml_key(key))
+    }
+}
+
+fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        key.to_string()
+    } else {
+        json!(key).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_check_layer() {
+        let values = json!({
+            "scops": ["api"],
+            "auto": { "git": { "psuh": true } },
+            "types": "feat",
+            "hooks": { "pre-comit": [], "pre-commit": [{ "command": "x", "cwdd": "." }] },
+            "api_key": { "anything": "goes" },
+            "branch": { "release/*": { "typs": [] }, "[": {} },
+        });
+        let Value::Object(values) = values else {
+            panic!()
+        };
+        let layer = Layer {
+            source: Source::Repo,
+            path: None,
+            values,
+        };
+        let mut findings = Vec::new();
+        check_layer(&layer, &mut findings);
+        let messages: Vec<&str> =
+            findings.iter().map(|f| f.message.as_str()).collect();
+        assert!(messages.contains(&"unknown key `scops`"));
+        assert!(messages.contains(&"unknown key `auto.git.psuh`"));
+        assert!(messages.contains(&"unknown key `hooks.pre-commit.cwdd`"));
+        assert!(
+            messages
+                .iter()
+                .any(|m| m.starts_with("`types`: invalid type"))
+        );
+        assert!(messages.iter().any(|m| m.starts_with("unknown hook phase")));
+        assert!(findings.iter().any(|f| {
+            f.location == r#"repo branch."release/*""#
+                && f.message == "unknown key `typs`"
+        }));
+        assert!(findings.iter().any(|f| f.location == r#"repo branch."[""#));
+        assert_eq!(findings.len(), 7);
+    }
+
+    #[test]
+    fn test_check_config() {
+        let mut config = ReviseConfig::default();
+        let mut findings = Vec::new();
+        check_config(&config, &mut findings);
+        assert!(findings.is_empty());
+
+        config.template = "{{ commit_typo }}".to_string();
+        config.types.push(config.types[0].clone());
+        config.scope_rules.push(ScopeRule {
+            scope: "api".to_string(),
+            files: vec!["crates/api/[".to_string()],
+        });
+        check_config(&config, &mut findings);
+        assert_eq!(findings.len(), 3);
+
+        let typed = |phase| {
+            let hook = Hook {
+                command: "./changelog.sh".to_string(),
+                types: vec!["feat".to_string()],
+                ..Hook::default()
+            };
+            let config = ReviseConfig {
+                hooks: HashMap::from([(phase, vec![hook])]),
+                ..ReviseConfig::default()
+            };
+            let mut findings = Vec::new();
+            check_config(&config, &mut findings);
+            findings.len()
+        };
+        assert_eq!(typed(HookType::PreCommit), 0);
+        assert_eq!(typed(HookType::PostAdd), 1);
+    }
+
+    #[test]
+    fn test_check_prompts() {
+        let ticket = Field {
+            name: "ticket".to_string(),
+            validate: Some(r"^PROJ-\d+$".to_string()),
+            ..Field::default()
+        };
+        let mut config = ReviseConfig {
+            template: "{{ commit_subject }} {{ ticket }}".to_string(),
+            fields: vec![ticket.clone()],
+            ..ReviseConfig::default()
+        };
+        let mut findings = Vec::new();
+        check_config(&config, &mut findings);
+        assert!(findings.is_empty());
+        assert_eq!(config.prompt_order().last().unwrap(), "ticket");
+
+        config.prompts = vec!["subject".to_string(), "tikcet".to_string()];
+        config.fields.push(Field {
+            name: "commit_kind".to_string(),
+            kind: FieldKind::Select,
+            validate: Some("[".to_string()),
+            ..Field::default()
+        });
+        check_config(&config, &mut findings);
+        let messages: Vec<&str> =
+            findings.iter().map(|f| f.message.as_str()).collect();
+        assert!(
+            messages
+                .contains(&"prompt `tikcet` is neither built in nor a field")
+        );
+        assert!(
+            messages.contains(
+                &"field `ticket` is not in `prompts`, so never asked"
+            )
+        );
+        assert!(
+            messages
+                .contains(&"field name `commit_kind` is taken by a built-in")
+        );
+        assert!(
+            messages.contains(&"select field `commit_kind` has no options")
+        );
+        assert_eq!(findings.len(), 6);
+    }
+
+    #[test]
+    fn test_inline() {
+        assert_eq!(
+            inline(
+                &json!({ "key": "feat", "pre-commit": [1, null], "a b": {} })
+            ),
+            r#"{ "a b" = {}, key = "feat", pre-commit = [1] }"#
+        );
+    }
+
+    #[test]
+    fn test_schema_is_published() {
+        let published = include_str!("../../revise.schema.json");
+        assert_eq!(
+            published.trim_end(),
+            schema().unwrap(),
+            "run `git revise config schema > revise.schema.json`"
+        );
+    }
+}
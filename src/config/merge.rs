@@ -0,0 +1,232 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// How a list of a higher layer is combined with the one below it.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Strategy {
+    Append,
+    Replace,
+}
+
+/// The lists whose strategy can be set in the `[merge]` table, with their
+/// default.
+pub const LISTS: [(&str, Strategy); 6] = [
+    ("types", Strategy::Replace),
+    ("fields", Strategy::Append),
+    ("scopes", Strategy::Append),
+    ("scope_rules", Strategy::Append),
+    ("exclude_files", Strategy::Append),
+    ("hooks", Strategy::Append),
+];
+
+/// Merges `layer` over `base`. Tables are merged key by key and other values
+/// are replaced, except for the lists above, which are appended to when
+/// their strategy says so. Appended `types` and `fields` replace the ones
+/// with the same key or name, and duplicates of other entries are dropped.
+pub fn merge_layer(
+    base: &mut Map<String, Value>,
+    mut layer: Map<String, Value>,
+) {
+    if let Some(strategies) = layer.remove("merge") {
+        deep_merge(base.entry("merge").or_insert(Value::Null), strategies);
+    }
+    let appended = appended_lists(base);
+    for (key, value) in layer {
+        let append = appended.contains(&key.as_str());
+        let slot = base.entry(key.clone()).or_insert(Value::Null);
+        match key.as_str() {
+            "hooks" if append => {
+                let (Value::Object(phases), Value::Object(layer)) =
+                    (&mut *slot, &value)
+                else {
+                    *slot = value;
+                    continue;
+                };
+                for (phase, hooks) in layer {
+                    let slot =
+                        phases.entry(phase.clone()).or_insert(Value::Null);
+                    append_list(slot, hooks.clone(), None);
+                }
+            }
+            "types" if append => append_list(slot, value, Some("key")),
+            "fields" if append => append_list(slot, value, Some("name")),
+            _ if append => append_list(slot, value, None),
+            _ => deep_merge(slot, value),
+        }
+    }
+}
+
+/// The lists that the `[merge]` table of `config` appends to.
+pub fn appended_lists(config: &Map<String, Value>) -> Vec<&'static str> {
+    LISTS
+        .iter()
+        .filter(|(list, default)| {
+            config
+                .get("merge")
+                .and_then(|merge| merge.get(list))
+                .and_then(|s| Strategy::deserialize(s).ok())
+                .unwrap_or(*default)
+                == Strategy::Append
+        })
+        .map(|(list, _)| *list)
+        .collect()
+}
+
+/// Prefix of the variables that set config values. Other `REVISE_*`
+/// variables, like the ones hooks get or the ones `"{{env}}"` reads, are
+/// not config keys.
+pub const ENV_PREFIX: &str = "REVISE_CONFIG__";
+
+/// The `REVISE_CONFIG__*` variables as a layer, with `__` separating nested
+/// keys: `REVISE_CONFIG__AUTO__GIT__PUSH=true` sets `auto.git.push`. Values
+/// are read as JSON when they parse, so lists and booleans can be given, and
+/// as strings otherwise.
+pub fn env_layer(
+    vars: impl Iterator<Item = (String, String)>,
+) -> Map<String, Value> {
+    let mut layer = Map::new();
+    for (name, value) in vars {
+        let Some(key) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let value =
+            serde_json::from_str(&value).unwrap_or(Value::String(value));
+        let mut path: Vec<String> =
+            key.to_lowercase().split("__").map(String::from).collect();
+        let leaf = path.pop().unwrap_or_default();
+        let mut table = &mut layer;
+        for part in path {
+            let entry = table
+                .entry(part)
+                .or_insert_with(|| Value::Object(Map::new()));
+            if !entry.is_object() {
+                *entry = Value::Object(Map::new());
+            }
+            let Value::Object(next) = entry else {
+                unreachable!()
+            };
+            table = next;
+        }
+        table.insert(leaf, value);
+    }
+    layer
+}
+
+fn deep_merge(slot: &mut Value, value: Value) {
+    match (slot, value) {
+        (Value::Object(base), Value::Object(layer)) => {
+            for (key, value) in layer {
+                deep_merge(base.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (slot, value) => *slot = value,
+    }
+}
+
+fn append_list(slot: &mut Value, value: Value, key: Option<&str>) {
+    let (Value::Array(base), Value::Array(items)) = (&mut *slot, &value) else {
+        *slot = value;
+        return;
+    };
+    for item in items {
+        let same = |existing: &Value| match key {
+            Some(key) => {
+                existing.get(key).is_some()
+                    && existing.get(key) == item.get(key)
+            }
+            None => existing == item,
+        };
+        match base.iter_mut().find(|existing| same(existing)) {
+            Some(existing) => existing.clone_from(item),
+            None => base.push(item.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn layer(value: Value) -> Map<String, Value> {
+        let Value::Object(map) = value else { panic!() };
+        map
+    }
+
+    #[test]
+    fn test_merge_layer() {
+        let mut base = layer(json!({
+            "api_key": { "gemini_key": "user" },
+            "auto": { "git": { "add": true, "push": false } },
+            "types": [{ "key": "feat", "value": "A feature" }],
+            "scopes": ["cli"],
+            "hooks": { "pre-commit": [{ "command": "cargo fmt" }] },
+        }));
+        merge_layer(
+            &mut base,
+            layer(json!({
+                "auto": { "git": { "push": true } },
+                "types": [{ "key": "fix", "value": "A fix" }],
+                "scopes": ["cli", "docs"],
+                "hooks": { "pre-commit": [{ "command": "cargo test" }] },
+            })),
+        );
+        assert_eq!(
+            Value::Object(base.clone()),
+            json!({
+                "api_key": { "gemini_key": "user" },
+                "auto": { "git": { "add": true, "push": true } },
+                "types": [{ "key": "fix", "value": "A fix" }],
+                "scopes": ["cli", "docs"],
+                "hooks": { "pre-commit": [
+                    { "command": "cargo fmt" },
+                    { "command": "cargo test" },
+                ] },
+            })
+        );
+
+        merge_layer(
+            &mut base,
+            layer(json!({
+                "merge": { "types": "append", "scopes": "replace" },
+                "types": [{ "key": "fix", "value": "Bug fix" }, { "key": "ci" }],
+                "scopes": ["web"],
+            })),
+        );
+        assert_eq!(
+            base["types"],
+            json!([{ "key": "fix", "value": "Bug fix" }, { "key": "ci" }])
+        );
+        assert_eq!(base["scopes"], json!(["web"]));
+    }
+
+    #[test]
+    fn test_env_layer() {
+        let vars = [
+            ("REVISE_CONFIG__AUTO__GIT__PUSH", "true"),
+            ("REVISE_CONFIG__API_KEY__GEMINI_KEY", "secret"),
+            ("REVISE_CONFIG__SCOPES", r#"["cli"]"#),
+            // what hooks get, and what `{{env}}` reads
+            ("REVISE_BRANCH", "main"),
+            ("REVISE_SCOPE", "api"),
+            ("REVISE_GEMINI_KEY", "secret"),
+            ("HOME", "/root"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let env = env_layer(vars.into_iter());
+        assert_eq!(
+            Value::Object(env.clone()),
+            json!({
+                "auto": { "git": { "push": true } },
+                "api_key": { "gemini_key": "secret" },
+                "scopes": ["cli"],
+            })
+        );
+        let cfg: crate::config::ReviseConfig =
+            serde_json::from_value(Value::Object(env)).unwrap();
+        assert!(cfg.auto.git.push);
+        assert_eq!(cfg.scopes, vec!["cli"]);
+    }
+}
@@ -0,0 +1,146 @@
+use serde_json::{Map, Value};
+
+use super::Source;
+use crate::{
+    error::{ReviseError, ReviseResult},
+    git::{
+        GitUtils,
+        pathspec::{BranchMatcher, PathMatcher},
+        repo::GitRepository,
+    },
+};
+
+/// The tables holding override sections, with the source they are recorded
+/// as.
+pub const KINDS: [(&str, Source); 2] =
+    [("branch", Source::Branch), ("path", Source::Path)];
+
+/// A `[branch."<glob>"]` or `[path."<glob>"]` section.
+#[derive(Debug, Clone)]
+pub struct Section {
+    pub source: Source,
+    pub pattern: String,
+    pub values: Map<String, Value>,
+}
+
+impl Section {
+    /// The section as written in the config, e.g. `branch."release/*"`.
+    pub fn name(&self) -> String {
+        format!("{}.{}", self.source.name(), Value::from(&*self.pattern))
+    }
+
+    /// Whether the section applies to `branch` and the staged `files`. A
+    /// branch glob is matched against the whole branch name, a path glob
+    /// the way `.gitignore` does.
+    pub fn applies(
+        &self,
+        branch: Option<&str>,
+        files: &[String],
+    ) -> ReviseResult<bool> {
+        let pattern = std::slice::from_ref(&self.pattern);
+        Ok(if self.source == Source::Branch {
+            let matcher = BranchMatcher::new(pattern)?;
+            branch.is_some_and(|b| matcher.is_match(b))
+        } else {
+            let matcher = PathMatcher::new(pattern)?;
+            files.iter().any(|f| matcher.is_match(f))
+        })
+    }
+}
+
+/// Every override section of `config`.
+pub fn sections(config: &Map<String, Value>) -> Vec<Section> {
+    let mut sections = Vec::new();
+    for (kind, source) in KINDS {
+        let Some(Value::Object(table)) = config.get(kind) else {
+            continue;
+        };
+        for (pattern, values) in table {
+            sections.push(Section {
+                source,
+                pattern: pattern.clone(),
+                values: values.as_object().cloned().unwrap_or_default(),
+            });
+        }
+    }
+    sections
+}
+
+/// The sections that apply to `branch` and the staged `files`, in the order
+/// they are merged: branch sections before path sections, and shorter globs
+/// before longer, more specific ones. A path section applies when any staged
+/// file matches.
+pub fn matching(
+    config: &Map<String, Value>,
+    branch: Option<&str>,
+    files: &[String],
+) -> ReviseResult<Vec<Section>> {
+    let mut matching = Vec::new();
+    for section in sections(config) {
+        let applies = section.applies(branch, files).map_err(|e| {
+            ReviseError::Config(format!("{}: {e}", section.name()))
+        })?;
+        if applies {
+            matching.push(section);
+        }
+    }
+    matching.sort_by_key(|s| (s.source != Source::Branch, s.pattern.len()));
+    Ok(matching)
+}
+
+/// The sections that apply to the checked out branch and the files staged
+/// right now. Git is only asked when the config has sections.
+pub fn current(config: &Map<String, Value>) -> ReviseResult<Vec<Section>> {
+    if sections(config).is_empty() {
+        return Ok(Vec::new());
+    }
+    let branch = GitUtils::git_current_branch().ok().flatten();
+    let files = GitUtils::new().staged_files().unwrap_or_default();
+    matching(config, branch.as_deref(), &files)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_matching() {
+        let Value::Object(config) = json!({
+            "branch": {
+                "release/*": { "lint": { "rules": { "issue-required": "error" } } },
+                "release/v2*": { "types": [{ "key": "fix", "value": "A fix" }] },
+                "main": { "scopes": ["main"] },
+            },
+            "path": { "docs/**": { "types": [{ "key": "docs", "value": "Docs" }] } },
+        }) else {
+            panic!()
+        };
+        let names = |branch, files: &[&str]| -> Vec<String> {
+            let files: Vec<String> =
+                files.iter().map(ToString::to_string).collect();
+            matching(&config, branch, &files)
+                .unwrap()
+                .iter()
+                .map(Section::name)
+                .collect()
+        };
+
+        assert_eq!(
+            names(Some("release/v2.1"), &["docs/intro.md", "src/lib.rs"]),
+            vec![
+                r#"branch."release/*""#,
+                r#"branch."release/v2*""#,
+                r#"path."docs/**""#
+            ]
+        );
+        assert_eq!(
+            names(Some("main"), &["src/lib.rs"]),
+            vec![r#"branch."main""#]
+        );
+        assert!(names(Some("feature/main"), &[]).is_empty());
+        assert!(names(Some("release/a/b"), &[]).is_empty());
+        assert!(names(None, &[]).is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+use realme::{Adaptor, Realme, StringSource, TomlParser};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{Emoji, Type};
+
+/// A built-in set of types, emojis, template and lint rules, selected with
+/// `preset = "<name>"`.
+#[derive(
+    Deserialize,
+    Serialize,
+    JsonSchema,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    /// Conventional Commits with an emoji, the default.
+    Conventional,
+    /// The Angular guidelines, without emojis.
+    Angular,
+    /// The full gitmoji spec, with `:code:` emojis and no type.
+    Gitmoji,
+    /// Linux kernel style `subsystem: subject`.
+    Kernel,
+}
+
+/// The part of a preset every config needs, see [`Preset::base`].
+#[derive(Deserialize)]
+pub struct Base {
+    pub types: Vec<Type>,
+    pub emojis: Vec<Emoji>,
+    pub template: String,
+}
+
+impl std::fmt::Display for Preset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let description = match self {
+            Self::Conventional => "Conventional Commits with an emoji",
+            Self::Angular => "the Angular guidelines, without emojis",
+            Self::Gitmoji => "the gitmoji spec, with :code: emojis",
+            Self::Kernel => "Linux kernel style, subsystem: subject",
+        };
+        write!(f, "{}: {description}", self.name())
+    }
+}
+
+impl Preset {
+    pub const ALL: [Self; 4] = [
+        Self::Conventional,
+        Self::Angular,
+        Self::Gitmoji,
+        Self::Kernel,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Conventional => "conventional",
+            Self::Angular => "angular",
+            Self::Gitmoji => "gitmoji",
+            Self::Kernel => "kernel",
+        }
+    }
+
+    const fn source(self) -> &'static str {
+        match self {
+            Self::Conventional => include_str!("presets/conventional.toml"),
+            Self::Angular => include_str!("presets/angular.toml"),
+            Self::Gitmoji => include_str!("presets/gitmoji.toml"),
+            Self::Kernel => include_str!("presets/kernel.toml"),
+        }
+    }
+
+    /// The preset as a config layer.
+    pub fn layer(self) -> Map<String, Value> {
+        let layer: Value = Realme::builder()
+            .load(Adaptor::new(StringSource::<TomlParser>::new(self.source())))
+            .build()
+            .and_then(|realme| realme.try_deserialize())
+            .expect("built-in presets are valid TOML");
+        match layer {
+            Value::Object(layer) => layer,
+            _ => Map::new(),
+        }
+    }
+
+    /// The types, emojis and template of the preset.
+    pub fn base(self) -> Base {
+        serde_json::from_value(Value::Object(self.layer()))
+            .expect("built-in presets have types, emojis and a template")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ReviseConfig;
+
+    #[test]
+    fn test_presets() {
+        for preset in Preset::ALL {
+            let config: ReviseConfig =
+                serde_json::from_value(Value::Object(preset.layer())).unwrap();
+            assert!(!config.types.is_empty(), "{}", preset.name());
+            assert!(
+                config.template.contains("commit_subject"),
+                "{}",
+                preset.name()
+            );
+        }
+        let gitmoji = Preset::Gitmoji.base();
+        assert_eq!(gitmoji.types.len(), gitmoji.emojis.len());
+        assert!(gitmoji.emojis.iter().any(|e| e.value == ":sparkles:"));
+    }
+}
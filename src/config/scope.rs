@@ -0,0 +1,129 @@
+use std::path::Path;
+
+use super::{ReviseConfig, workspace};
+use crate::git::pathspec::PathMatcher;
+
+/// The scopes to offer, and which of them a path belongs to.
+#[derive(Debug, Clone, Default)]
+pub struct Scopes {
+    /// Every scope, configured ones first.
+    pub names: Vec<String>,
+    rules: Vec<(String, PathMatcher)>,
+    /// Detected directories, with their scope.
+    dirs: Vec<(String, String)>,
+}
+
+impl Scopes {
+    /// The scopes of `cfg`, plus the packages and top-level directories of
+    /// `root` when `detect_scopes` is set.
+    pub fn new(cfg: &ReviseConfig, root: Option<&Path>) -> Self {
+        let mut scopes = Self {
+            names: cfg.get_scopes(),
+            ..Self::default()
+        };
+        for rule in &cfg.scope_rules {
+            // `config validate` reports invalid globs
+            if let Ok(matcher) = PathMatcher::new(&rule.files) {
+                scopes.rules.push((rule.scope.clone(), matcher));
+            }
+        }
+        if let Some(root) = root.filter(|_| cfg.detect_scopes) {
+            let packages = workspace::packages(root);
+            for dir in &packages {
+                let scope = dir.rsplit('/').next().unwrap_or(dir);
+                scopes.dirs.push((dir.clone(), scope.to_string()));
+            }
+            for dir in workspace::top_level_dirs(root, &packages) {
+                scopes.dirs.push((dir.clone(), dir));
+            }
+            for (_, scope) in &scopes.dirs {
+                if !scopes.names.contains(scope) {
+                    scopes.names.push(scope.clone());
+                }
+            }
+        }
+        scopes
+    }
+
+    /// The scopes of `files`, in the order of [`Self::names`]. A file matched
+    /// by a `scope_rules` entry gets only the scopes of the rules, otherwise
+    /// the scope of the innermost detected directory it is in.
+    pub fn touched(&self, files: &[String]) -> Vec<String> {
+        let mut touched = Vec::new();
+        for file in files {
+            let matched: Vec<&String> = self
+                .rules
+                .iter()
+                .filter(|(_, matcher)| matcher.is_match(file))
+                .map(|(scope, _)| scope)
+                .collect();
+            if !matched.is_empty() {
+                touched.extend(matched);
+                continue;
+            }
+            if let Some((_, scope)) = self
+                .dirs
+                .iter()
+                .filter(|(dir, _)| {
+                    file.strip_prefix(dir.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+                })
+                .max_by_key(|(dir, _)| dir.len())
+            {
+                touched.push(scope);
+            }
+        }
+        self.names
+            .iter()
+            .filter(|name| touched.contains(name))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::config::ScopeRule;
+
+    #[test]
+    fn test_touched_scopes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for path in ["crates/api", "crates/cli", "docs", "web"] {
+            fs::create_dir_all(root.join(path)).unwrap();
+        }
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+
+        let mut cfg = ReviseConfig {
+            scopes: vec!["deps".to_string()],
+            scope_rules: vec![ScopeRule {
+                scope: "deps".to_string(),
+                files: vec!["Cargo.lock".to_string()],
+            }],
+            ..ReviseConfig::default()
+        };
+        let files = [
+            "crates/api/src/lib.rs",
+            "web/index.ts",
+            "Cargo.lock",
+            "README.md",
+        ]
+        .map(String::from);
+
+        let scopes = Scopes::new(&cfg, Some(root));
+        assert_eq!(scopes.names, vec!["deps"]);
+        assert_eq!(scopes.touched(&files), vec!["deps"]);
+
+        cfg.detect_scopes = true;
+        let scopes = Scopes::new(&cfg, Some(root));
+        assert_eq!(scopes.names, vec!["deps", "api", "cli", "docs", "web"]);
+        assert_eq!(scopes.touched(&files), vec!["deps", "api", "web"]);
+    }
+}
@@ -0,0 +1,180 @@
+use std::{fs, path::Path};
+
+use globset::{GlobBuilder, GlobSetBuilder};
+use realme::{Adaptor, FileSource, Realme, TomlParser};
+use serde_json::Value;
+
+use crate::error::ReviseResult;
+
+/// Directories that never hold workspace packages.
+const IGNORED: [&str; 3] = ["target", "node_modules", "vendor"];
+
+/// The package directories of the Cargo, npm or pnpm workspace at `root`,
+/// relative to it and sorted.
+pub fn packages(root: &Path) -> Vec<String> {
+    let mut patterns = cargo_members(root).unwrap_or_default();
+    patterns.extend(npm_workspaces(root).unwrap_or_default());
+    patterns.extend(pnpm_packages(root));
+    if patterns.is_empty() {
+        return Vec::new();
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for pattern in &patterns {
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+        if let Ok(glob) =
+            GlobBuilder::new(pattern).literal_separator(true).build()
+        {
+            builder.add(glob);
+        }
+    }
+    let Ok(set) = builder.build() else {
+        return Vec::new();
+    };
+    let depth = patterns
+        .iter()
+        .map(|p| p.split('/').filter(|c| !c.is_empty() && *c != ".").count())
+        .max()
+        .unwrap_or(1);
+
+    let mut dirs = Vec::new();
+    walk(root, "", depth, &mut dirs);
+    dirs.retain(|dir| set.is_match(dir));
+    dirs.sort();
+    dirs
+}
+
+/// The names of the workspace packages, to offer as scopes.
+pub fn detect_scopes(root: &Path) -> Vec<String> {
+    let mut scopes: Vec<String> = packages(root)
+        .iter()
+        .filter_map(|dir| dir.rsplit('/').next().map(ToString::to_string))
+        .collect();
+    scopes.sort();
+    scopes.dedup();
+    scopes
+}
+
+/// The top-level directories of `root` that are not part of a workspace
+/// package, sorted.
+pub fn top_level_dirs(root: &Path, packages: &[String]) -> Vec<String> {
+    let mut dirs = Vec::new();
+    walk(root, "", 1, &mut dirs);
+    dirs.retain(|dir| {
+        !packages
+            .iter()
+            .any(|package| package.split('/').next() == Some(dir.as_str()))
+    });
+    dirs.sort();
+    dirs
+}
+
+fn walk(dir: &Path, prefix: &str, depth: usize, out: &mut Vec<String>) {
+    if depth == 0 {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.')
+            || IGNORED.contains(&name.as_str())
+            || !entry.path().is_dir()
+        {
+            continue;
+        }
+        let relative = if prefix.is_empty() {
+            name
+        } else {
+            format!("{prefix}/{name}")
+        };
+        walk(&entry.path(), &relative, depth - 1, out);
+        out.push(relative);
+    }
+}
+
+/// `workspace.members` of the root `Cargo.toml`.
+fn cargo_members(root: &Path) -> ReviseResult<Vec<String>> {
+    let path = root.join("Cargo.toml");
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    let manifest: Value = Realme::builder()
+        .load(Adaptor::new(FileSource::<TomlParser>::new(path)))
+        .build()?
+        .try_deserialize()?;
+    Ok(strings(&manifest["workspace"]["members"]))
+}
+
+/// `workspaces` of `package.json`, either a list or `{ packages = [...] }`.
+fn npm_workspaces(root: &Path) -> ReviseResult<Vec<String>> {
+    let path = root.join("package.json");
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    let package: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let workspaces = &package["workspaces"];
+    Ok(if workspaces.is_array() {
+        strings(workspaces)
+    } else {
+        strings(&workspaces["packages"])
+    })
+}
+
+/// The `packages` list of `pnpm-workspace.yaml`, read line by line.
+fn pnpm_packages(root: &Path) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(root.join("pnpm-workspace.yaml"))
+    else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .skip_while(|line| !line.starts_with("packages:"))
+        .skip(1)
+        .take_while(|line| line.starts_with(' ') || line.trim().is_empty())
+        .filter_map(|line| line.trim().strip_prefix("- "))
+        .map(|item| item.trim_matches(|c| c == '"' || c == '\'').to_string())
+        // `!pattern` excludes
+        .filter(|item| !item.starts_with('!'))
+        .collect()
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str().map(ToString::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_scopes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for package in ["crates/api", "crates/cli", "web", "crates/.hidden"] {
+            fs::create_dir_all(root.join(package)).unwrap();
+        }
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+        fs::write(root.join("package.json"), r#"{"workspaces": ["web"]}"#)
+            .unwrap();
+
+        assert_eq!(packages(root), vec!["crates/api", "crates/cli", "web"]);
+        assert_eq!(detect_scopes(root), vec!["api", "cli", "web"]);
+
+        fs::create_dir_all(root.join("docs")).unwrap();
+        assert_eq!(top_level_dirs(root, &packages(root)), vec!["docs"]);
+    }
+}
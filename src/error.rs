@@ -1 +1,200 @@
+use colored::Colorize;
+use thiserror::Error;
+
 pub type ReviseResult<T> = anyhow::Result<T>;
+
+/// Exit code for a failing configured hook.
+pub const HOOK_FAILED: exitcode::ExitCode = 80;
+/// Exit code when the secret scanner refuses to send a diff.
+pub const SECRETS_DETECTED: exitcode::ExitCode = 81;
+/// Exit code when the user cancels a prompt, as with SIGINT in a shell.
+pub const CANCELLED: exitcode::ExitCode = 130;
+
+/// Failures that callers (scripts, CI wrappers) may want to tell apart.
+///
+/// Everything else stays a plain `anyhow::Error`; `main` downcasts to this
+/// type to pick the exit code and hint.
+#[derive(Debug, Error)]
+pub enum ReviseError {
+    #[error("Invalid configuration: {0}")]
+    Config(String),
+    #[error("Git {command} failed: {stderr}")]
+    Git { command: String, stderr: String },
+    #[error("Hook failed: {command} (exit code: {code:?})")]
+    Hook { command: String, code: Option<i32> },
+    #[error("Hook timed out after {seconds}s: {command}")]
+    HookTimeout { command: String, seconds: u64 },
+    #[error("The AI provider rejected the API key: {0}")]
+    AiAuth(String),
+    #[error("The AI provider quota is exhausted: {0}")]
+    AiQuota(String),
+    #[error("Could not reach the AI provider: {0}")]
+    AiNetwork(String),
+    #[error("Secrets detected in the staged diff ({0} finding(s))")]
+    SecretsDetected(usize),
+    #[error("Operation cancelled")]
+    Cancelled,
+    #[error("Nothing staged to commit")]
+    NothingStaged,
+    #[error("{0}")]
+    Validation(String),
+}
+
+impl ReviseError {
+    pub fn git(command: &str, stderr: &[u8]) -> Self {
+        Self::Git {
+            command: command.to_string(),
+            stderr: String::from_utf8_lossy(stderr).trim().to_string(),
+        }
+    }
+
+    pub const fn exit_code(&self) -> exitcode::ExitCode {
+        match self {
+            Self::Config(_) => exitcode::CONFIG,
+            Self::Git { .. } => exitcode::SOFTWARE,
+            Self::Hook { .. } | Self::HookTimeout { .. } => HOOK_FAILED,
+            Self::AiAuth(_) => exitcode::NOPERM,
+            Self::AiQuota(_) => exitcode::TEMPFAIL,
+            Self::AiNetwork(_) => exitcode::UNAVAILABLE,
+            Self::SecretsDetected(_) => SECRETS_DETECTED,
+            Self::Cancelled => CANCELLED,
+            Self::NothingStaged => exitcode::NOINPUT,
+            Self::Validation(_) => exitcode::DATAERR,
+        }
+    }
+
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::Config(_) => Some(
+                "Run `git revise config validate` to see what is wrong, or \
+                 remove the file to use the defaults",
+            ),
+            Self::Git { command, stderr } => git_hint(command, stderr),
+            Self::Hook { .. } => Some(
+                "Fix the failing command, or set `skip = true` or \
+                 `on_failure = \"warn\"` on the hook in revise.toml",
+            ),
+            Self::HookTimeout { .. } => {
+                Some("Raise the hook's `timeout` in revise.toml")
+            }
+            Self::AiAuth(_) => {
+                Some("Check `api_key.gemini_key` in revise.toml")
+            }
+            Self::AiQuota(_) => {
+                Some("Wait a moment and retry, or commit without `-g`")
+            }
+            Self::AiNetwork(_) => {
+                Some("Check your network connection and proxy settings")
+            }
+            Self::SecretsDetected(_) => Some(
+                "Remove the secrets from the staged files, or add them to \
+                 `ai_exclude`",
+            ),
+            Self::NothingStaged => Some(
+                "Stage changes with `git add <paths>` or `git-revise -a \
+                 <paths>`",
+            ),
+            Self::Cancelled | Self::Validation(_) => None,
+        }
+    }
+}
+
+/// A hint for a failed git command, when its output tells what went wrong.
+fn git_hint(command: &str, stderr: &str) -> Option<&'static str> {
+    if stderr.contains("not a git repository") {
+        return Some(
+            "Run it inside a git repository, or point at one with `-C <path>`",
+        );
+    }
+    if stderr.contains("Please tell me who you are") {
+        return Some(
+            "Set your identity with `git config user.name` and `git config \
+             user.email`",
+        );
+    }
+    match command {
+        "commit" => Some(
+            "A git hook may have rejected the commit, see its output above; \
+             `--no-verify` skips the hooks",
+        ),
+        "rebase" => Some("The rebase was aborted, the branch is unchanged"),
+        _ => None,
+    }
+}
+
+/// Turns an aborted inquire prompt into [`ReviseError::Cancelled`], leaving
+/// other errors untouched.
+pub fn map_cancelled(err: anyhow::Error) -> anyhow::Error {
+    if let Some(
+        inquire::InquireError::OperationCanceled
+        | inquire::InquireError::OperationInterrupted,
+    ) = err.downcast_ref()
+    {
+        ReviseError::Cancelled.into()
+    } else {
+        err
+    }
+}
+
+/// Maps any error to the exit code `main` should use.
+pub fn exit_code(err: &anyhow::Error) -> exitcode::ExitCode {
+    if let Some(e) = err.downcast_ref::<ReviseError>() {
+        return e.exit_code();
+    }
+    if let Some(
+        inquire::InquireError::OperationCanceled
+        | inquire::InquireError::OperationInterrupted,
+    ) = err.downcast_ref()
+    {
+        return CANCELLED;
+    }
+    1
+}
+
+/// Prints the error and, for known failures, a hint on how to fix it.
+pub fn report(err: &anyhow::Error) {
+    eprintln!("{} {err}", "error:".red().bold());
+    if let Some(hint) = err
+        .downcast_ref::<ReviseError>()
+        .and_then(ReviseError::hint)
+    {
+        eprintln!("{} {hint}", "hint:".yellow().bold());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code() {
+        let err = anyhow::Error::new(ReviseError::NothingStaged);
+        assert_eq!(exit_code(&err), exitcode::NOINPUT);
+
+        let err = anyhow::Error::new(inquire::InquireError::OperationCanceled);
+        assert_eq!(exit_code(&err), CANCELLED);
+
+        let err = anyhow::anyhow!("something else");
+        assert_eq!(exit_code(&err), 1);
+    }
+
+    #[test]
+    fn test_git_hint() {
+        let hint = |command, stderr: &str| {
+            ReviseError::git(command, stderr.as_bytes()).hint()
+        };
+        assert!(
+            hint("status", "fatal: not a git repository (or any parent)")
+                .is_some_and(|h| h.contains("-C"))
+        );
+        assert!(
+            hint("commit", "cargo fmt --check failed")
+                .is_some_and(|h| h.contains("--no-verify"))
+        );
+        assert!(
+            hint("rebase", "CONFLICT (content): Merge conflict in a.rs")
+                .is_some_and(|h| h.contains("aborted"))
+        );
+        assert_eq!(hint("diff", "fatal: bad revision 'x'"), None);
+    }
+}
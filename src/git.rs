@@ -1,26 +1,32 @@
-use add::GitAdd;
-use cmit::GitCommit;
-use diff::GitDiff;
+use backend::{CliBackend, GitBackend};
+use hunk::FileDiff;
+use push::GitPush;
+use rebase::GitRebase;
 use repo::GitRepository;
+use status::StatusEntry;
 
 use crate::ReviseResult;
 
 pub mod add;
+pub mod backend;
 pub mod cmit;
 pub mod diff;
+pub mod hunk;
+#[cfg(feature = "git2")]
+pub mod native;
+pub mod pathspec;
+pub mod push;
+pub mod rebase;
 pub mod repo;
+pub mod status;
 
-pub struct GitUtils;
-
-// pub struct GitUtils {
-//     repo: git2::Repository,
-// }
+pub struct GitUtils {
+    backend: Box<dyn GitBackend>,
+}
 
-// impl Default for GitUtils {
-//     fn default() -> Self {
-//         Self::new()
-//     }
-// }
+impl GitPush for GitUtils {}
+impl GitRebase for GitUtils {}
+impl GitRepository for GitUtils {}
 
 impl Default for GitUtils {
     fn default() -> Self {
@@ -29,21 +35,56 @@ impl Default for GitUtils {
 }
 
 impl GitUtils {
-    pub const fn new() -> Self {
-        Self {}
+    /// Uses the `git` binary, or the native backend when built with the
+    /// `git2` feature, `REVISE_GIT_BACKEND=git2` is set and the repository
+    /// can be opened.
+    pub fn new() -> Self {
+        #[cfg(feature = "git2")]
+        if std::env::var("REVISE_GIT_BACKEND").is_ok_and(|b| b == "git2") {
+            if let Ok(native) = native::NativeBackend::open_from_env() {
+                return Self::with_backend(native);
+            }
+        }
+        Self::with_backend(CliBackend)
+    }
+    pub fn with_backend(backend: impl GitBackend + 'static) -> Self {
+        Self {
+            backend: Box::new(backend),
+        }
+    }
+    pub fn repo_root(&self) -> ReviseResult<String> {
+        self.backend.repo_root()
     }
     pub fn diff(&self, exclude_files: &[String]) -> ReviseResult<String> {
-        Self::git_diff(exclude_files)
+        self.backend.diff_staged(exclude_files)
+    }
+    pub fn commit(&self, message: &str, no_verify: bool) -> ReviseResult<()> {
+        self.backend.commit(message, no_verify)
+    }
+    pub fn amend(&self, message: &str, no_verify: bool) -> ReviseResult<()> {
+        self.backend.amend(message, no_verify)
     }
-    pub fn commit(&self, message: &str) -> ReviseResult<()> {
-        Self::git_cmit(message)
+    pub fn head_message(&self) -> ReviseResult<String> {
+        self.backend.head_message()
     }
     pub fn add(&self, paths: &[String]) -> ReviseResult<()> {
-        Self::git_add(paths)
+        self.backend.add(paths)
+    }
+    pub fn status(&self) -> ReviseResult<Vec<StatusEntry>> {
+        self.backend.status()
+    }
+    pub fn staged_files(&self) -> ReviseResult<Vec<String>> {
+        self.backend.staged_files()
+    }
+    pub fn unstaged_hunks(
+        &self,
+        exclude_files: &[String],
+    ) -> ReviseResult<Vec<FileDiff>> {
+        Ok(hunk::parse_diff(
+            &self.backend.diff_unstaged(exclude_files)?,
+        ))
+    }
+    pub fn apply_cached(&self, patch: &str) -> ReviseResult<()> {
+        self.backend.apply_cached(patch)
     }
 }
-
-impl GitDiff for GitUtils {}
-impl GitCommit for GitUtils {}
-impl GitRepository for GitUtils {}
-impl GitAdd for GitUtils {}
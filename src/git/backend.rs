@@ -0,0 +1,76 @@
+use super::{
+    add::GitAdd,
+    cmit::GitCommit,
+    diff::GitDiff,
+    hunk::GitHunk,
+    repo::GitRepository,
+    status::{GitStatus, StatusEntry},
+};
+use crate::error::ReviseResult;
+
+/// The repository operations revise needs, independent of how they are
+/// carried out.
+pub trait GitBackend {
+    /// Absolute path of the work tree root.
+    fn repo_root(&self) -> ReviseResult<String>;
+    /// Diff of the index against `HEAD`.
+    fn diff_staged(&self, exclude_files: &[String]) -> ReviseResult<String>;
+    /// Diff of the work tree against the index.
+    fn diff_unstaged(&self, exclude_files: &[String]) -> ReviseResult<String>;
+    fn add(&self, paths: &[String]) -> ReviseResult<()>;
+    /// Applies a patch to the index only.
+    fn apply_cached(&self, patch: &str) -> ReviseResult<()>;
+    /// `no_verify` skips the repository's own git hooks, where the backend
+    /// runs them at all.
+    fn commit(&self, message: &str, no_verify: bool) -> ReviseResult<()>;
+    /// Rewrites `HEAD` with the given message and the current index.
+    fn amend(&self, message: &str, no_verify: bool) -> ReviseResult<()>;
+    /// The full message of `HEAD`.
+    fn head_message(&self) -> ReviseResult<String>;
+    fn status(&self) -> ReviseResult<Vec<StatusEntry>>;
+    fn staged_files(&self) -> ReviseResult<Vec<String>>;
+}
+
+/// Shells out to the `git` binary on `PATH`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CliBackend;
+
+impl GitDiff for CliBackend {}
+impl GitCommit for CliBackend {}
+impl GitRepository for CliBackend {}
+impl GitAdd for CliBackend {}
+impl GitHunk for CliBackend {}
+impl GitStatus for CliBackend {}
+
+impl GitBackend for CliBackend {
+    fn repo_root(&self) -> ReviseResult<String> {
+        Self::git_repo()
+    }
+    fn diff_staged(&self, exclude_files: &[String]) -> ReviseResult<String> {
+        Self::git_diff(exclude_files)
+    }
+    fn diff_unstaged(&self, exclude_files: &[String]) -> ReviseResult<String> {
+        Self::git_unstaged_diff(exclude_files)
+    }
+    fn add(&self, paths: &[String]) -> ReviseResult<()> {
+        Self::git_add(paths)
+    }
+    fn apply_cached(&self, patch: &str) -> ReviseResult<()> {
+        Self::git_apply_cached(patch)
+    }
+    fn commit(&self, message: &str, no_verify: bool) -> ReviseResult<()> {
+        Self::git_cmit(message, no_verify)
+    }
+    fn amend(&self, message: &str, no_verify: bool) -> ReviseResult<()> {
+        Self::git_cmit_amend(message, no_verify)
+    }
+    fn head_message(&self) -> ReviseResult<String> {
+        Self::git_head_message()
+    }
+    fn status(&self) -> ReviseResult<Vec<StatusEntry>> {
+        Self::git_status()
+    }
+    fn staged_files(&self) -> ReviseResult<Vec<String>> {
+        Self::git_staged_files()
+    }
+}
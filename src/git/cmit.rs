@@ -1,53 +1,51 @@
-// pub trait GitCommit {
-//     fn git_cmit(
-//         repo: &git2::Repository,
-//         message: &str,
-//     ) -> Result<(), git2::Error> {
-//         // 写入index
-//         let mut index = repo.index()?;
-//         let oid = index.write_tree()?;
-
-//         let tree = repo.find_tree(oid)?;
-
-//         // 获取当前HEAD的commit
-//         let head = repo.head().ok();
-//         let parent_commit = head.as_ref().and_then(|h|
-// h.peel_to_commit().ok());
-
-//         let parents = parent_commit.iter().collect::<Vec<_>>();
-
-//         let conf = git2::Config::open_default()?;
-//         let name = conf.get_string("user.name")?;
-//         let email = conf.get_string("user.email")?;
-//         let signature = git2::Signature::now(&name, &email)?;
-
-//         repo.commit(
-//             Some("HEAD"),
-//             &signature,
-//             &signature,
-//             message,
-//             &tree,
-//             &parents,
-//         )?;
-
-//         Ok(())
-//     }
-// }
 use std::process::Command;
 
-use crate::error::ReviseResult;
+use crate::error::{ReviseError, ReviseResult};
 
 pub trait GitCommit {
-    fn git_cmit(message: &str) -> ReviseResult<()> {
+    /// `no_verify` skips the repository's own `pre-commit` and `commit-msg`
+    /// hooks.
+    fn git_cmit(message: &str, no_verify: bool) -> ReviseResult<()> {
+        let mut args = vec!["commit", "-m", message];
+        if no_verify {
+            args.push("--no-verify");
+        }
+        Self::git_cmit_with(&args)
+    }
+
+    /// Replaces the message (and tree, with whatever is staged) of `HEAD`.
+    fn git_cmit_amend(message: &str, no_verify: bool) -> ReviseResult<()> {
+        let mut args = vec!["commit", "--amend", "-m", message];
+        if no_verify {
+            args.push("--no-verify");
+        }
+        Self::git_cmit_with(&args)
+    }
+
+    fn git_head_message() -> ReviseResult<String> {
         let output = Command::new("git")
-            .args(["commit", "-m", message])
+            .args(["log", "-1", "--format=%B", "HEAD"])
             .output()?;
 
         if !output.status.success() {
-            return Err(anyhow::anyhow!(
-                "Git commit failed: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ));
+            return Err(ReviseError::git("log", &output.stderr).into());
+        }
+
+        Ok(String::from_utf8(output.stdout)?.trim_end().to_string())
+    }
+
+    fn git_cmit_with(args: &[&str]) -> ReviseResult<()> {
+        let output = Command::new("git").args(args).output()?;
+
+        if !output.status.success() {
+            // git reports an empty index on stdout, not stderr
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if stdout.contains("nothing to commit")
+                || stdout.contains("no changes added to commit")
+            {
+                return Err(ReviseError::NothingStaged.into());
+            }
+            return Err(ReviseError::git("commit", &output.stderr).into());
         }
 
         Ok(())
@@ -0,0 +1,176 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use crate::error::{ReviseError, ReviseResult};
+
+/// The changes of a single file in a unified diff.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileDiff {
+    pub path: String,
+    /// `diff --git`, `index`, `---` and `+++` lines.
+    pub header: Vec<String>,
+    pub hunks: Vec<Hunk>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hunk {
+    /// The `@@ -a,b +c,d @@` line.
+    pub header: String,
+    pub lines: Vec<String>,
+}
+
+impl FileDiff {
+    /// Builds a patch that only contains the hunks at `selected`.
+    pub fn patch(&self, selected: &[usize]) -> String {
+        let mut patch = String::new();
+        for line in &self.header {
+            patch.push_str(line);
+            patch.push('\n');
+        }
+        for (idx, hunk) in self.hunks.iter().enumerate() {
+            if !selected.contains(&idx) {
+                continue;
+            }
+            patch.push_str(&hunk.header);
+            patch.push('\n');
+            for line in &hunk.lines {
+                patch.push_str(line);
+                patch.push('\n');
+            }
+        }
+        patch
+    }
+}
+
+impl Hunk {
+    /// A one line summary: the hunk range and its first changed line.
+    pub fn summary(&self) -> String {
+        let first = self
+            .lines
+            .iter()
+            .find(|l| l.starts_with('+') || l.starts_with('-'))
+            .map_or("", String::as_str);
+        let range = self
+            .header
+            .split("@@")
+            .nth(1)
+            .map_or(self.header.as_str(), str::trim);
+        format!("@@ {range} @@ {first}")
+    }
+}
+
+/// Splits a unified diff into files and hunks. Files without hunks (binary
+/// files, pure mode changes) are left out since they can't be split.
+pub fn parse_diff(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            files.push(FileDiff {
+                path: rest
+                    .split_once(" b/")
+                    .map_or(rest, |(_, b)| b)
+                    .to_string(),
+                header: vec![line.to_string()],
+                hunks: Vec::new(),
+            });
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        if line.starts_with("@@") {
+            file.hunks.push(Hunk {
+                header: line.to_string(),
+                lines: Vec::new(),
+            });
+        } else if let Some(hunk) = file.hunks.last_mut() {
+            hunk.lines.push(line.to_string());
+        } else {
+            file.header.push(line.to_string());
+        }
+    }
+    files.retain(|f| !f.hunks.is_empty());
+    files
+}
+
+pub trait GitHunk {
+    fn git_unstaged_diff(exclude_files: &[String]) -> ReviseResult<String> {
+        let mut args = vec!["diff", "--no-color", "--no-ext-diff"];
+
+        let exclude_args: Vec<String> = exclude_files
+            .iter()
+            .map(|file| format!(":!{file}"))
+            .collect();
+        args.extend(exclude_args.iter().map(String::as_str));
+
+        let output = Command::new("git").args(&args).output()?;
+
+        if !output.status.success() {
+            return Err(ReviseError::git("diff", &output.stderr).into());
+        }
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    fn git_apply_cached(patch: &str) -> ReviseResult<()> {
+        let mut child = Command::new("git")
+            .args(["apply", "--cached", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(patch.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+
+        if !output.status.success() {
+            return Err(ReviseError::git("apply", &output.stderr).into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+ a
+-b
++B
+ c
+@@ -10,3 +10,4 @@ fn main() {
+ x
+ y
++z
+ w
+diff --git a/logo.png b/logo.png
+index 3333333..4444444 100644
+Binary files a/logo.png and b/logo.png differ
+";
+
+    #[test]
+    fn test_parse_diff() {
+        let files = parse_diff(DIFF);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].header.len(), 4);
+        assert_eq!(files[0].hunks.len(), 2);
+        assert_eq!(files[0].hunks[1].summary(), "@@ -10,3 +10,4 @@ +z");
+    }
+
+    #[test]
+    fn test_patch_with_selected_hunks() {
+        let files = parse_diff(DIFF);
+        let patch = files[0].patch(&[1]);
+        assert!(patch.starts_with("diff --git a/src/lib.rs b/src/lib.rs\n"));
+        assert!(!patch.contains("+B"));
+        assert!(patch.ends_with("+z\n w\n"));
+    }
+}
@@ -0,0 +1,454 @@
+use std::path::{Component, Path, PathBuf};
+
+use git2::{
+    ApplyLocation, Diff, DiffFormat, ErrorCode, IndexAddOption, Repository,
+    Status, StatusOptions, Tree,
+};
+
+use super::{
+    backend::{CliBackend, GitBackend},
+    pathspec::PathMatcher,
+    status::StatusEntry,
+};
+use crate::error::{ReviseError, ReviseResult};
+
+/// Hooks `git commit` runs, which libgit2 doesn't.
+const COMMIT_HOOKS: [&str; 5] = [
+    "pre-commit",
+    "prepare-commit-msg",
+    "commit-msg",
+    "post-commit",
+    "post-rewrite",
+];
+
+/// Diffs, stages and commits in-process through libgit2. The queries of
+/// [`GitRepository`](super::repo::GitRepository) and friends still run the
+/// `git` binary. libgit2 neither runs the repository's own `.git/hooks` nor
+/// signs commits, so commits go through `git` when there are commit hooks or
+/// `commit.gpgsign` is set.
+pub struct NativeBackend {
+    repo: Repository,
+}
+
+impl std::fmt::Debug for NativeBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NativeBackend")
+            .field("path", &self.repo.path())
+            .finish()
+    }
+}
+
+fn wrap<T>(f: impl FnOnce() -> Result<T, git2::Error>) -> ReviseResult<T> {
+    f().map_err(|e| ReviseError::git("libgit2", e.message().as_bytes()).into())
+}
+
+impl NativeBackend {
+    /// Opens the repository the same way `git` would, honouring `GIT_DIR`
+    /// and friends.
+    pub fn open_from_env() -> ReviseResult<Self> {
+        wrap(|| Ok(Self::new(Repository::open_from_env()?)))
+    }
+
+    pub fn discover(path: impl AsRef<Path>) -> ReviseResult<Self> {
+        wrap(|| Ok(Self::new(Repository::discover(path)?)))
+    }
+
+    pub const fn new(repo: Repository) -> Self {
+        Self { repo }
+    }
+
+    fn head_tree(&self) -> Result<Option<Tree<'_>>, git2::Error> {
+        match self.repo.head() {
+            Ok(head) => Ok(Some(head.peel_to_tree()?)),
+            Err(e)
+                if matches!(
+                    e.code(),
+                    ErrorCode::UnbornBranch | ErrorCode::NotFound
+                ) =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn staged(&self) -> Result<Diff<'_>, git2::Error> {
+        let tree = self.head_tree()?;
+        let index = self.repo.index()?;
+        self.repo
+            .diff_tree_to_index(tree.as_ref(), Some(&index), None)
+    }
+
+    /// Whether a commit has to go through the `git` binary, which runs the
+    /// repository's hooks and signs commits when `commit.gpgsign` is set.
+    fn needs_cli(&self) -> bool {
+        let Ok(config) = self.repo.config() else {
+            return true;
+        };
+        if config.get_bool("commit.gpgsign").unwrap_or(false) {
+            return true;
+        }
+        let hooks = config.get_path("core.hooksPath").map_or_else(
+            |_| self.repo.commondir().join("hooks"),
+            |path| match self.repo.workdir() {
+                Some(root) if path.is_relative() => root.join(path),
+                _ => path,
+            },
+        );
+        COMMIT_HOOKS.iter().any(|name| hooks.join(name).is_file())
+    }
+
+    /// Maps a path given relative to the current directory, like the `git`
+    /// CLI expects, to a pathspec relative to the work tree. A `:/` path from
+    /// [`top`](super::pathspec::top) is already relative to the work tree.
+    fn pathspec(&self, path: &str) -> String {
+        let (prefix, path) = match path.strip_prefix(":/") {
+            Some(path) => (PathBuf::new(), path),
+            None => (self.cwd_prefix(), path),
+        };
+
+        let mut normalized = PathBuf::new();
+        for component in prefix.join(path).components() {
+            match component {
+                Component::ParentDir => {
+                    normalized.pop();
+                }
+                Component::Normal(c) => normalized.push(c),
+                _ => {}
+            }
+        }
+        let spec = normalized.to_string_lossy().replace('\\', "/");
+        if spec.is_empty() {
+            "*".to_string()
+        } else {
+            spec
+        }
+    }
+
+    /// Where the current directory is in the work tree.
+    fn cwd_prefix(&self) -> PathBuf {
+        self.repo
+            .workdir()
+            .zip(std::env::current_dir().ok())
+            .and_then(|(root, cwd)| {
+                let root = root.canonicalize().ok()?;
+                let cwd = cwd.canonicalize().ok()?;
+                cwd.strip_prefix(root).ok().map(Path::to_path_buf)
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn render(diff: &Diff<'_>, excludes: &PathMatcher) -> ReviseResult<String> {
+    let mut out = String::new();
+    wrap(|| {
+        diff.print(DiffFormat::Patch, |delta, _, line| {
+            let path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_default();
+            if excludes.is_match(&path) {
+                return true;
+            }
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                out.push(line.origin());
+            }
+            out.push_str(&String::from_utf8_lossy(line.content()));
+            true
+        })
+    })?;
+    Ok(out)
+}
+
+const fn status_chars(status: Status) -> (char, char) {
+    if status.contains(Status::CONFLICTED) {
+        return ('U', 'U');
+    }
+    if status.contains(Status::WT_NEW) {
+        return ('?', '?');
+    }
+    let index = if status.contains(Status::INDEX_NEW) {
+        'A'
+    } else if status.contains(Status::INDEX_MODIFIED) {
+        'M'
+    } else if status.contains(Status::INDEX_DELETED) {
+        'D'
+    } else if status.contains(Status::INDEX_RENAMED) {
+        'R'
+    } else if status.contains(Status::INDEX_TYPECHANGE) {
+        'T'
+    } else {
+        ' '
+    };
+    let worktree = if status.contains(Status::WT_MODIFIED) {
+        'M'
+    } else if status.contains(Status::WT_DELETED) {
+        'D'
+    } else if status.contains(Status::WT_RENAMED) {
+        'R'
+    } else if status.contains(Status::WT_TYPECHANGE) {
+        'T'
+    } else {
+        ' '
+    };
+    (index, worktree)
+}
+
+impl GitBackend for NativeBackend {
+    fn repo_root(&self) -> ReviseResult<String> {
+        let root = self.repo.workdir().ok_or_else(|| {
+            ReviseError::git("libgit2", b"bare repositories have no work tree")
+        })?;
+        Ok(root
+            .to_string_lossy()
+            .trim_end_matches(['/', '\\'])
+            .to_string())
+    }
+
+    fn diff_staged(&self, exclude_files: &[String]) -> ReviseResult<String> {
+        let excludes = PathMatcher::new(exclude_files)?;
+        let diff = wrap(|| self.staged())?;
+        render(&diff, &excludes)
+    }
+
+    fn diff_unstaged(&self, exclude_files: &[String]) -> ReviseResult<String> {
+        let excludes = PathMatcher::new(exclude_files)?;
+        let diff = wrap(|| self.repo.diff_index_to_workdir(None, None))?;
+        render(&diff, &excludes)
+    }
+
+    fn add(&self, paths: &[String]) -> ReviseResult<()> {
+        let specs: Vec<String> =
+            paths.iter().map(|p| self.pathspec(p)).collect();
+        wrap(|| {
+            let mut index = self.repo.index()?;
+            index.add_all(specs.iter(), IndexAddOption::DEFAULT, None)?;
+            // add_all only picks up new and modified files
+            index.update_all(specs.iter(), None)?;
+            index.write()
+        })
+    }
+
+    fn apply_cached(&self, patch: &str) -> ReviseResult<()> {
+        wrap(|| {
+            let diff = Diff::from_buffer(patch.as_bytes())?;
+            self.repo.apply(&diff, ApplyLocation::Index, None)
+        })
+    }
+
+    // libgit2 never runs the repository's hooks, so `no_verify` is moot
+    fn commit(&self, message: &str, no_verify: bool) -> ReviseResult<()> {
+        if self.needs_cli() {
+            return CliBackend.commit(message, no_verify);
+        }
+        let committed = wrap(|| {
+            let mut index = self.repo.index()?;
+            let oid = index.write_tree()?;
+            let tree = self.repo.find_tree(oid)?;
+            let parent = match self.repo.head() {
+                Ok(head) => Some(head.peel_to_commit()?),
+                Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+                Err(e) => return Err(e),
+            };
+            // a first commit has nothing to compare with but the empty tree
+            let unchanged = parent
+                .as_ref()
+                .map_or_else(|| tree.is_empty(), |p| p.tree_id() == oid);
+            if unchanged {
+                return Ok(false);
+            }
+            let parents: Vec<_> = parent.iter().collect();
+            let signature = self.repo.signature()?;
+            let message = git2::message_prettify(message, None)?;
+            self.repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                &message,
+                &tree,
+                &parents,
+            )?;
+            Ok(true)
+        })?;
+        if !committed {
+            return Err(ReviseError::NothingStaged.into());
+        }
+        Ok(())
+    }
+
+    fn amend(&self, message: &str, no_verify: bool) -> ReviseResult<()> {
+        if self.needs_cli() {
+            return CliBackend.amend(message, no_verify);
+        }
+        wrap(|| {
+            let head = self.repo.head()?.peel_to_commit()?;
+            let tree = self.repo.find_tree(self.repo.index()?.write_tree()?)?;
+            let message = git2::message_prettify(message, None)?;
+            head.amend(
+                Some("HEAD"),
+                None,
+                None,
+                None,
+                Some(&message),
+                Some(&tree),
+            )?;
+            Ok(())
+        })
+    }
+
+    fn head_message(&self) -> ReviseResult<String> {
+        wrap(|| {
+            let head = self.repo.head()?.peel_to_commit()?;
+            Ok(String::from_utf8_lossy(head.message_bytes())
+                .trim_end()
+                .to_string())
+        })
+    }
+
+    fn status(&self) -> ReviseResult<Vec<StatusEntry>> {
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .renames_head_to_index(true);
+        wrap(|| {
+            let statuses = self.repo.statuses(Some(&mut opts))?;
+            Ok(statuses
+                .iter()
+                .filter(|s| !s.status().contains(Status::IGNORED))
+                .map(|s| {
+                    let (index, worktree) = status_chars(s.status());
+                    let path = s
+                        .head_to_index()
+                        .and_then(|d| d.new_file().path())
+                        .map(|p| p.to_string_lossy().into_owned())
+                        .or_else(|| s.path().map(ToString::to_string))
+                        .unwrap_or_default();
+                    StatusEntry {
+                        index,
+                        worktree,
+                        path,
+                    }
+                })
+                .collect())
+        })
+    }
+
+    fn staged_files(&self) -> ReviseResult<Vec<String>> {
+        wrap(|| {
+            Ok(self
+                .staged()?
+                .deltas()
+                .filter_map(|d| {
+                    d.new_file().path().or_else(|| d.old_file().path())
+                })
+                .map(|p| p.to_string_lossy().into_owned())
+                .collect())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::git::pathspec;
+
+    fn init() -> (tempfile::TempDir, NativeBackend) {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut cfg = repo.config().unwrap();
+        cfg.set_str("user.name", "revise").unwrap();
+        cfg.set_str("user.email", "revise@example.com").unwrap();
+        (dir, NativeBackend::new(repo))
+    }
+
+    #[test]
+    fn test_stage_and_commit() {
+        let (dir, git) = init();
+        fs::write(dir.path().join("a.txt"), "a\n").unwrap();
+        fs::write(dir.path().join("b.txt"), "b\n").unwrap();
+
+        let status = git.status().unwrap();
+        assert_eq!(status.len(), 2);
+        assert!(status.iter().all(StatusEntry::is_untracked));
+        assert!(git.staged_files().unwrap().is_empty());
+
+        git.add(&["a.txt".to_string()]).unwrap();
+        assert_eq!(git.staged_files().unwrap(), vec!["a.txt".to_string()]);
+        let diff = git.diff_staged(&[]).unwrap();
+        assert!(diff.starts_with("diff --git a/a.txt b/a.txt"));
+        assert!(diff.contains("+a\n"));
+        assert!(git.diff_staged(&["a.txt".to_string()]).unwrap().is_empty());
+
+        git.commit("feat: add a\n\n\n", false).unwrap();
+        let head = git.repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message(), Some("feat: add a\n"));
+        assert!(git.staged_files().unwrap().is_empty());
+
+        let err = git.commit("chore: nothing", false).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ReviseError>(),
+            Some(ReviseError::NothingStaged)
+        ));
+
+        fs::write(dir.path().join("b.txt"), "b\nb\n").unwrap();
+        git.add(&[pathspec::top("b.txt")]).unwrap();
+        git.amend("feat: add a and b", false).unwrap();
+        assert_eq!(git.head_message().unwrap(), "feat: add a and b");
+        let head = git.repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.parent_count(), 0);
+        assert!(head.tree().unwrap().get_name("b.txt").is_some());
+    }
+
+    #[test]
+    fn test_commit_nothing_on_unborn_branch() {
+        let (_dir, git) = init();
+        let err = git.commit("feat: nothing", false).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ReviseError>(),
+            Some(ReviseError::NothingStaged)
+        ));
+        assert!(git.repo.head().is_err());
+    }
+
+    #[test]
+    fn test_needs_cli() {
+        let (dir, git) = init();
+        assert!(!git.needs_cli());
+
+        let hooks = dir.path().join(".git/hooks");
+        fs::create_dir_all(&hooks).unwrap();
+        fs::write(hooks.join("commit-msg"), "#!/bin/sh\n").unwrap();
+        assert!(git.needs_cli());
+        fs::remove_file(hooks.join("commit-msg")).unwrap();
+
+        let mut cfg = git.repo.config().unwrap();
+        cfg.set_bool("commit.gpgsign", true).unwrap();
+        assert!(git.needs_cli());
+    }
+
+    #[test]
+    fn test_apply_cached_hunk() {
+        let (dir, git) = init();
+        let content = (1..=30).map(|i| i.to_string()).collect::<Vec<_>>();
+        let content = content.join("\n") + "\n";
+        fs::write(dir.path().join("f"), &content).unwrap();
+        git.add(&["f".to_string()]).unwrap();
+        git.commit("init", false).unwrap();
+
+        let changed =
+            content.replace("\n2\n", "\ntwo\n").replace("25\n", "x\n");
+        fs::write(dir.path().join("f"), changed).unwrap();
+        let files =
+            super::super::hunk::parse_diff(&git.diff_unstaged(&[]).unwrap());
+        assert_eq!(files[0].hunks.len(), 2);
+
+        git.apply_cached(&files[0].patch(&[1])).unwrap();
+        let staged = git.diff_staged(&[]).unwrap();
+        assert!(staged.contains("+x\n"));
+        assert!(!staged.contains("+two\n"));
+    }
+}
@@ -0,0 +1,101 @@
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+
+use crate::error::ReviseResult;
+
+/// Matches repository-relative paths against a list of globs.
+///
+/// A pattern without a `/` matches the file name at any depth, the same way
+/// `.gitignore` treats it.
+#[derive(Debug, Clone)]
+pub struct PathMatcher {
+    set: GlobSet,
+}
+
+impl PathMatcher {
+    pub fn new(patterns: &[String]) -> ReviseResult<Self> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Glob::new(pattern)?);
+            if !pattern.contains('/') {
+                builder.add(Glob::new(&format!("**/{pattern}"))?);
+            }
+        }
+        Ok(Self {
+            set: builder.build()?,
+        })
+    }
+
+    pub fn is_match(&self, path: &str) -> bool {
+        self.set.is_match(path)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+}
+
+/// Matches branch names against a list of globs, like `git branch --list`:
+/// `*` stays within one `/`-separated part and a pattern is matched against
+/// the whole name, so `main` doesn't match `feature/main`.
+#[derive(Debug, Clone)]
+pub struct BranchMatcher {
+    set: GlobSet,
+}
+
+impl BranchMatcher {
+    pub fn new(patterns: &[String]) -> ReviseResult<Self> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(
+                GlobBuilder::new(pattern).literal_separator(true).build()?,
+            );
+        }
+        Ok(Self {
+            set: builder.build()?,
+        })
+    }
+
+    pub fn is_match(&self, branch: &str) -> bool {
+        self.set.is_match(branch)
+    }
+}
+
+/// The pathspec of a path relative to the repository root, which git reads
+/// from there wherever it runs.
+pub fn top(path: &str) -> String {
+    format!(":/{path}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_matcher() {
+        let m = PathMatcher::new(&[
+            "CHANGELOG.md".to_string(),
+            "docs/**".to_string(),
+        ])
+        .unwrap();
+        assert!(m.is_match("CHANGELOG.md"));
+        assert!(m.is_match("crates/api/CHANGELOG.md"));
+        assert!(m.is_match("docs/guide/intro.md"));
+        assert!(!m.is_match("src/docs/intro.md"));
+        assert!(!m.is_match("README.md"));
+    }
+
+    #[test]
+    fn test_branch_matcher() {
+        let m = BranchMatcher::new(&[
+            "main".to_string(),
+            "release/*".to_string(),
+            "hotfix/**".to_string(),
+        ])
+        .unwrap();
+        assert!(m.is_match("main"));
+        assert!(!m.is_match("feature/main"));
+        assert!(m.is_match("release/1.0"));
+        assert!(!m.is_match("release/a/b"));
+        assert!(m.is_match("hotfix/a/b"));
+    }
+}
@@ -0,0 +1,60 @@
+use super::repo::GitRepository;
+use crate::error::{ReviseError, ReviseResult};
+
+/// Pushing the current branch, built on the queries of [`GitRepository`].
+pub trait GitPush: GitRepository {
+    /// The upstream of the current branch, e.g. `origin/main`.
+    fn git_upstream() -> ReviseResult<Option<String>> {
+        Self::git_query(&[
+            "rev-parse",
+            "--abbrev-ref",
+            "--symbolic-full-name",
+            "@{u}",
+        ])
+    }
+
+    /// Remote a first push of `branch` should go to: the configured push
+    /// remote, else `origin`, else the only remote there is.
+    fn git_push_remote(branch: &str) -> ReviseResult<Option<String>> {
+        let key = format!("branch.{branch}.pushRemote");
+        for key in [key.as_str(), "remote.pushDefault"] {
+            if let Some(remote) = Self::git_query(&["config", "--get", key])? {
+                return Ok(Some(remote));
+            }
+        }
+        let remotes: Vec<String> = Self::git_query(&["remote"])?
+            .unwrap_or_default()
+            .lines()
+            .map(ToString::to_string)
+            .collect();
+        if remotes.iter().any(|r| r == "origin") {
+            return Ok(Some("origin".to_string()));
+        }
+        Ok(match remotes.as_slice() {
+            [remote] => Some(remote.clone()),
+            _ => None,
+        })
+    }
+
+    /// Pushes the current branch, setting its upstream on the first push.
+    /// Git's own output (progress, credential prompts) goes to the terminal.
+    fn git_push() -> ReviseResult<()> {
+        if Self::git_upstream()?.is_some() {
+            return Self::git_interactive("push", &[]);
+        }
+        let branch = Self::git_current_branch()?.ok_or_else(|| {
+            ReviseError::Validation(
+                "HEAD is detached, there is no branch to push".to_string(),
+            )
+        })?;
+        let remote = Self::git_push_remote(&branch)?.ok_or_else(|| {
+            ReviseError::Validation(
+                "There is no remote to push to, add one with `git remote \
+                 add`"
+                    .to_string(),
+            )
+        })?;
+        println!("Pushing {branch} to {remote} and setting it as upstream");
+        Self::git_interactive("push", &["--set-upstream", &remote, &branch])
+    }
+}
@@ -0,0 +1,110 @@
+use std::{path::Path, process::Command};
+
+use crate::error::{ReviseError, ReviseResult};
+
+/// History rewriting helpers. These always go through the `git` binary,
+/// since only it knows how to run a rebase.
+pub trait GitRebase {
+    fn git_output(args: &[&str]) -> ReviseResult<String> {
+        let output = Command::new("git").args(args).output()?;
+
+        if !output.status.success() {
+            return Err(ReviseError::git(args[0], &output.stderr).into());
+        }
+
+        Ok(String::from_utf8(output.stdout)?.trim_end().to_string())
+    }
+
+    fn git_rev_parse(rev: &str) -> ReviseResult<String> {
+        Self::git_output(&["rev-parse", "--verify", "--quiet", rev])
+    }
+
+    /// Commits in `range`, oldest first.
+    fn git_rev_list(range: &str) -> ReviseResult<Vec<String>> {
+        Ok(Self::git_output(&["rev-list", "--reverse", range])?
+            .lines()
+            .map(ToString::to_string)
+            .collect())
+    }
+
+    /// Commits in `range` that aren't merges, oldest first.
+    fn git_rev_list_no_merges(range: &str) -> ReviseResult<Vec<String>> {
+        Ok(
+            Self::git_output(&["rev-list", "--reverse", "--no-merges", range])?
+                .lines()
+                .map(ToString::to_string)
+                .collect(),
+        )
+    }
+
+    fn git_parent_count(sha: &str) -> ReviseResult<usize> {
+        let parents =
+            Self::git_output(&["rev-list", "--parents", "-n", "1", sha])?;
+        Ok(parents.split_whitespace().count().saturating_sub(1))
+    }
+
+    fn git_commit_message(sha: &str) -> ReviseResult<String> {
+        Self::git_output(&["log", "-1", "--format=%B", sha])
+    }
+
+    /// The patch introduced by a single commit.
+    fn git_commit_diff(
+        sha: &str,
+        exclude_files: &[String],
+    ) -> ReviseResult<String> {
+        let mut args = vec!["show", "--format=", "--no-color", sha, "--"];
+        let exclude_args: Vec<String> = exclude_files
+            .iter()
+            .map(|file| format!(":!{file}"))
+            .collect();
+        args.extend(exclude_args.iter().map(String::as_str));
+        Self::git_output(&args)
+    }
+
+    /// Remote-tracking branches that already contain `sha`.
+    fn git_remote_branches_containing(sha: &str) -> ReviseResult<Vec<String>> {
+        Ok(Self::git_output(&["branch", "-r", "--contains", sha])?
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect())
+    }
+
+    /// Whether tracked files have uncommitted changes.
+    fn git_is_dirty() -> ReviseResult<bool> {
+        Ok(!Self::git_output(&[
+            "status",
+            "--porcelain",
+            "--untracked-files=no",
+        ])?
+        .is_empty())
+    }
+
+    fn git_update_ref(name: &str, rev: &str) -> ReviseResult<()> {
+        Self::git_output(&["update-ref", name, rev]).map(|_| ())
+    }
+
+    /// Path of `name` inside the git directory, e.g. `.git/<name>`.
+    fn git_path(name: &str) -> ReviseResult<String> {
+        Self::git_output(&["rev-parse", "--git-path", name])
+    }
+
+    /// Runs `git rebase -i <base>` with `todo` as the instruction sheet, so
+    /// no editor is ever opened. An unsuccessful rebase is aborted. Paths
+    /// reach the shell through variables, never quoted into a command:
+    /// `exec` lines can name files next to `todo` as `"$REVISE_TODO_DIR/…"`.
+    fn git_rebase_with_todo(base: &str, todo: &Path) -> ReviseResult<()> {
+        let output = Command::new("git")
+            .args(["rebase", "-i", "--no-autosquash", base])
+            .env("GIT_SEQUENCE_EDITOR", r#"cp "$REVISE_TODO""#)
+            .env("REVISE_TODO", todo)
+            .env("REVISE_TODO_DIR", todo.parent().unwrap_or(todo))
+            .output()?;
+
+        if !output.status.success() {
+            let _ = Command::new("git").args(["rebase", "--abort"]).output();
+            return Err(ReviseError::git("rebase", &output.stderr).into());
+        }
+        Ok(())
+    }
+}
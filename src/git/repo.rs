@@ -1,11 +1,19 @@
-// pub trait GitRepository {
-//     fn git_repo() -> crate::error::ReviseResult<git2::Repository> {
-//         Ok(git2::Repository::open_from_env()?)
-//     }
-// }
-use std::process::Command;
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
 
-use crate::error::ReviseResult;
+use crate::error::{ReviseError, ReviseResult};
+
+/// Variables that tell git where the repository is, e.g. as set by git for
+/// the hooks it runs.
+const LOCATION_VARS: [&str; 5] = [
+    "GIT_DIR",
+    "GIT_WORK_TREE",
+    "GIT_COMMON_DIR",
+    "GIT_INDEX_FILE",
+    "GIT_OBJECT_DIRECTORY",
+];
 
 pub trait GitRepository {
     fn git_repo() -> ReviseResult<String> {
@@ -14,14 +22,106 @@ pub trait GitRepository {
             .output()?;
 
         if !output.status.success() {
-            return Err(anyhow::anyhow!(
-                "Failed to get git repository: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ));
+            return Err(ReviseError::git("rev-parse", &output.stderr).into());
         }
 
         Ok(String::from_utf8(output.stdout)?.trim().to_string())
     }
+
+    /// Directory git runs hooks from, honouring `core.hooksPath` and shared
+    /// by every worktree.
+    fn git_hooks_dir() -> ReviseResult<PathBuf> {
+        let output = Command::new("git")
+            .args(["rev-parse", "--git-path", "hooks"])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(ReviseError::git("rev-parse", &output.stderr).into());
+        }
+
+        Ok(PathBuf::from(String::from_utf8(output.stdout)?.trim()))
+    }
+
+    /// Work tree of the main checkout when the current one is a linked
+    /// worktree, `None` otherwise, e.g. in a submodule.
+    fn git_main_worktree() -> ReviseResult<Option<PathBuf>> {
+        let output = Command::new("git")
+            .args([
+                "rev-parse",
+                "--path-format=absolute",
+                "--git-dir",
+                "--git-common-dir",
+            ])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(ReviseError::git("rev-parse", &output.stderr).into());
+        }
+
+        let output = String::from_utf8(output.stdout)?;
+        let mut dirs = output.lines().map(PathBuf::from);
+        let (Some(git_dir), Some(common_dir)) = (dirs.next(), dirs.next())
+        else {
+            return Ok(None);
+        };
+        if git_dir == common_dir {
+            return Ok(None);
+        }
+        Ok(common_dir.parent().map(Path::to_path_buf))
+    }
+
+    /// Stdout of a git command that is allowed to fail, e.g. a missing
+    /// config key.
+    fn git_query(args: &[&str]) -> ReviseResult<Option<String>> {
+        let output = Command::new("git").args(args).output()?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        let stdout = String::from_utf8(output.stdout)?.trim().to_string();
+        Ok(Some(stdout).filter(|s| !s.is_empty()))
+    }
+
+    /// Name of the checked out branch, `None` on a detached `HEAD`.
+    fn git_current_branch() -> ReviseResult<Option<String>> {
+        Self::git_query(&["symbolic-ref", "--short", "-q", "HEAD"])
+    }
+
+    /// Runs `git <command> <args>` attached to the terminal.
+    fn git_interactive(command: &str, args: &[&str]) -> ReviseResult<()> {
+        let status = Command::new("git").arg(command).args(args).status()?;
+        if !status.success() {
+            return Err(ReviseError::Git {
+                command: command.to_string(),
+                stderr: format!("git exited with {status}"),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// The repository location variables that are set, made absolute against
+/// the current directory, so commands started elsewhere still find the same
+/// repository.
+pub fn location_env() -> Vec<(String, String)> {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    absolute_env(
+        LOCATION_VARS.iter().filter_map(|name| {
+            Some((name.to_string(), std::env::var(name).ok()?))
+        }),
+        &cwd,
+    )
+}
+
+fn absolute_env(
+    vars: impl Iterator<Item = (String, String)>,
+    cwd: &Path,
+) -> Vec<(String, String)> {
+    vars.filter(|(_, value)| !value.is_empty())
+        .map(|(name, value)| {
+            (name, cwd.join(value).to_string_lossy().to_string())
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -29,11 +129,24 @@ mod tests {
     use super::*;
 
     #[test]
-    #[ignore]
+    #[ignore = "requires a git repository"]
     fn test_git_repo() {
         struct GitRepoImpl;
         impl GitRepository for GitRepoImpl {}
         let repo = GitRepoImpl::git_repo().unwrap();
         println!("Git repository: {repo}");
     }
+
+    #[test]
+    fn test_absolute_env() {
+        let vars = [("GIT_DIR", ".git"), ("GIT_WORK_TREE", "/srv/repo")]
+            .map(|(k, v)| (k.to_string(), v.to_string()));
+        assert_eq!(
+            absolute_env(vars.into_iter(), Path::new("/home/me/repo")),
+            vec![
+                ("GIT_DIR".to_string(), "/home/me/repo/.git".to_string()),
+                ("GIT_WORK_TREE".to_string(), "/srv/repo".to_string()),
+            ]
+        );
+    }
 }
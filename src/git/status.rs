@@ -0,0 +1,104 @@
+use std::process::Command;
+
+use crate::error::{ReviseError, ReviseResult};
+
+/// One entry of `git status --porcelain`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusEntry {
+    /// Status of the path in the index (`X` column).
+    pub index: char,
+    /// Status of the path in the work tree (`Y` column).
+    pub worktree: char,
+    pub path: String,
+}
+
+impl StatusEntry {
+    pub const fn is_staged(&self) -> bool {
+        !matches!(self.index, ' ' | '?' | '!')
+    }
+
+    pub const fn is_untracked(&self) -> bool {
+        self.index == '?'
+    }
+
+    /// Whether the work tree has changes that are not staged yet.
+    pub const fn has_unstaged(&self) -> bool {
+        self.worktree != ' '
+    }
+}
+
+impl std::fmt::Display for StatusEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{} {}", self.index, self.worktree, self.path)
+    }
+}
+
+pub trait GitStatus {
+    fn git_status() -> ReviseResult<Vec<StatusEntry>> {
+        let output = Command::new("git")
+            .args(["status", "--porcelain", "-z", "--untracked-files=all"])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(ReviseError::git("status", &output.stderr).into());
+        }
+
+        Ok(parse_porcelain(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    fn git_staged_files() -> ReviseResult<Vec<String>> {
+        let output = Command::new("git")
+            .args(["diff", "--staged", "--name-only", "-z"])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(ReviseError::git("diff", &output.stderr).into());
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .split('\0')
+            .filter(|s| !s.is_empty())
+            .map(ToString::to_string)
+            .collect())
+    }
+}
+
+/// Parses the NUL separated output of `git status --porcelain -z`.
+pub fn parse_porcelain(output: &str) -> Vec<StatusEntry> {
+    let mut entries = Vec::new();
+    let mut fields = output.split('\0').filter(|s| !s.is_empty());
+    while let Some(field) = fields.next() {
+        let mut chars = field.chars();
+        let (Some(index), Some(worktree)) = (chars.next(), chars.next()) else {
+            continue;
+        };
+        let path = field.get(3..).unwrap_or_default().to_string();
+        // Renames and copies are followed by the original path.
+        if matches!(index, 'R' | 'C') {
+            fields.next();
+        }
+        entries.push(StatusEntry {
+            index,
+            worktree,
+            path,
+        });
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_porcelain() {
+        let entries =
+            parse_porcelain("RM b\0a\0?? sp ace.txt\0 M src/lib.rs\0");
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].path, "b");
+        assert!(entries[0].is_staged() && entries[0].has_unstaged());
+        assert!(entries[1].is_untracked() && !entries[1].is_staged());
+        assert_eq!(entries[1].path, "sp ace.txt");
+        assert!(!entries[2].is_staged() && entries[2].has_unstaged());
+    }
+}
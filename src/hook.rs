@@ -1,18 +1,33 @@
 // use crate::hooks::types::HookType;
 use std::{
-    process::{Command, Stdio},
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
     str::FromStr,
+    thread,
+    time::{Duration, Instant},
 };
 
+use colored::{Color, ColoredString, Colorize};
+use inquire::Confirm;
 use serde::{Deserialize, Serialize};
 
-use crate::error::ReviseResult;
+use crate::{
+    config::{Hook, OnFailure},
+    error::{ReviseError, ReviseResult},
+    git::{
+        pathspec::{BranchMatcher, PathMatcher},
+        repo,
+    },
+};
+
+pub mod install;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum HookType {
     PreAdd,
     PostAdd,
     PreCommit,
+    CommitMsg,
     PostCommit,
     PrePush,
     PostMerge,
@@ -26,11 +41,177 @@ impl FromStr for HookType {
             "pre-add" => Ok(Self::PreAdd),
             "post-add" => Ok(Self::PostAdd),
             "pre-commit" => Ok(Self::PreCommit),
+            "commit-msg" => Ok(Self::CommitMsg),
             "post-commit" => Ok(Self::PostCommit),
             "pre-push" => Ok(Self::PrePush),
             "post-merge" => Ok(Self::PostMerge),
-            _ => Err(anyhow::anyhow!("Invalid hook type: {}", s)),
+            _ => Err(anyhow::anyhow!("Invalid hook type: {s}")),
+        }
+    }
+}
+
+impl HookType {
+    pub const ALL: [Self; 7] = [
+        Self::PreAdd,
+        Self::PostAdd,
+        Self::PreCommit,
+        Self::CommitMsg,
+        Self::PostCommit,
+        Self::PrePush,
+        Self::PostMerge,
+    ];
+
+    /// The name used in revise.toml, e.g. `pre-commit`.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::PreAdd => "pre-add",
+            Self::PostAdd => "post-add",
+            Self::PreCommit => "pre-commit",
+            Self::CommitMsg => "commit-msg",
+            Self::PostCommit => "post-commit",
+            Self::PrePush => "pre-push",
+            Self::PostMerge => "post-merge",
+        }
+    }
+
+    /// Whether the commit type is known when the phase runs, so a `types`
+    /// filter can match. Pre-commit hooks with one wait for the prompts.
+    pub const fn knows_type(&self) -> bool {
+        !matches!(self, Self::PreAdd | Self::PostAdd | Self::PostMerge)
+    }
+}
+
+/// What is known about the commit in progress, handed to hooks as
+/// `REVISE_*` environment variables. Fields are filled in as the flow goes,
+/// e.g. `commit_sha` only exists from `post-commit` on.
+#[derive(Debug, Clone, Default)]
+pub struct HookContext {
+    pub repo_root: Option<PathBuf>,
+    pub branch: Option<String>,
+    pub commit_type: Option<String>,
+    pub scope: Option<String>,
+    pub subject: Option<String>,
+    pub message_file: Option<PathBuf>,
+    pub staged_files: Vec<String>,
+    pub commit_sha: Option<String>,
+}
+
+impl HookContext {
+    /// Environment for a hook of `phase`. Unknown values are exported empty
+    /// so scripts don't pick up stale ones from the parent shell.
+    pub fn envs(&self, phase: &HookType) -> Vec<(String, String)> {
+        let path = |p: &Option<PathBuf>| {
+            p.as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default()
+        };
+        vec![
+            ("REVISE_HOOK", phase.name().to_string()),
+            ("REVISE_REPO_ROOT", path(&self.repo_root)),
+            ("REVISE_BRANCH", self.branch.clone().unwrap_or_default()),
+            (
+                "REVISE_COMMIT_TYPE",
+                self.commit_type.clone().unwrap_or_default(),
+            ),
+            ("REVISE_SCOPE", self.scope.clone().unwrap_or_default()),
+            ("REVISE_SUBJECT", self.subject.clone().unwrap_or_default()),
+            ("REVISE_MESSAGE_FILE", path(&self.message_file)),
+            ("REVISE_STAGED_FILES", self.staged_files.join("\n")),
+            (
+                "REVISE_COMMIT_SHA",
+                self.commit_sha.clone().unwrap_or_default(),
+            ),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        // hooks with a `cwd` still work on this repository
+        .chain(repo::location_env())
+        .collect()
+    }
+
+    /// The staged files `hook` applies to, or `None` when its `branches`,
+    /// `types` or `files` filters rule it out. A `types` filter never matches
+    /// before the message is known.
+    pub fn matching_files(
+        &self,
+        hook: &Hook,
+    ) -> ReviseResult<Option<Vec<String>>> {
+        if !hook.branches.is_empty() {
+            let branches = BranchMatcher::new(&hook.branches)?;
+            if !self.branch.as_deref().is_some_and(|b| branches.is_match(b)) {
+                return Ok(None);
+            }
+        }
+        if !hook.types.is_empty()
+            && !self
+                .commit_type
+                .as_ref()
+                .is_some_and(|t| hook.types.contains(t))
+        {
+            return Ok(None);
+        }
+
+        let files = PathMatcher::new(&hook.files)?;
+        let exclude = PathMatcher::new(&hook.exclude)?;
+        let matching: Vec<String> = self
+            .staged_files
+            .iter()
+            .filter(|f| {
+                (files.is_empty() || files.is_match(f)) && !exclude.is_match(f)
+            })
+            .cloned()
+            .collect();
+        if (!files.is_empty() || !exclude.is_empty()) && matching.is_empty() {
+            return Ok(None);
         }
+        Ok(Some(matching))
+    }
+}
+
+/// Which configured hooks a run may use, from `--no-hooks`, `--skip-hook`
+/// and `--only-hook`.
+#[derive(Debug, Clone, Default)]
+pub struct HookFilter {
+    pub disabled: bool,
+    /// Hook names (or commands) to skip.
+    pub skip: Vec<String>,
+    /// Phases to run, all of them when empty.
+    pub only: Vec<HookType>,
+}
+
+impl HookFilter {
+    pub fn allows_phase(&self, phase: &HookType) -> bool {
+        !self.disabled && (self.only.is_empty() || self.only.contains(phase))
+    }
+
+    pub fn skips(&self, hook: &Hook) -> bool {
+        self.skip
+            .iter()
+            .any(|s| hook.name.as_ref() == Some(s) || hook.command == *s)
+    }
+}
+
+/// How a hook ended, for the summary printed after each phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStatus {
+    Passed,
+    Failed,
+    TimedOut,
+    /// Failed, but its `on_failure` let the run carry on.
+    Ignored,
+    Skipped,
+}
+
+impl HookStatus {
+    fn label(self) -> ColoredString {
+        let (label, color) = match self {
+            Self::Passed => ("ok", Color::Green),
+            Self::Failed => ("failed", Color::Red),
+            Self::TimedOut => ("timeout", Color::Red),
+            Self::Ignored => ("ignored", Color::Yellow),
+            Self::Skipped => ("skipped", Color::BrightBlack),
+        };
+        format!("{label:<8}").color(color)
     }
 }
 
@@ -45,35 +226,314 @@ impl HookRunner {
     }
 
     pub fn run_command(command: &str) -> ReviseResult<()> {
-        let output = if cfg!(target_os = "windows") {
-            Command::new("cmd")
-                .arg("/C")
-                .arg(command)
-                .stdout(Stdio::inherit())
-                .stderr(Stdio::inherit())
-                .output()?
+        Self::run_command_in(command, &[], None, None)
+    }
+
+    /// Runs the hooks of a phase that apply to `context` by `order`, those
+    /// sharing an order and a `parallel_group` at the same time, then prints
+    /// a summary table.
+    pub fn run_phase(
+        phase: &HookType,
+        hooks: &[Hook],
+        context: &HookContext,
+    ) -> ReviseResult<()> {
+        let mut sorted_hooks: Vec<_> = hooks.iter().collect();
+        sorted_hooks.sort_by_key(|h| {
+            (h.order.unwrap_or(u32::MAX), h.parallel_group.clone())
+        });
+
+        let mut summary = Vec::new();
+        let mut result = Ok(());
+        let mut hooks = Vec::new();
+        for &hook in &sorted_hooks {
+            match context.matching_files(hook)? {
+                Some(files) if !hook.skip.unwrap_or(false) => {
+                    hooks.push((hook, files));
+                }
+                _ => summary.push((hook, HookStatus::Skipped, Duration::ZERO)),
+            }
+        }
+        let envs = context.envs(phase);
+        let root = context.repo_root.as_deref();
+
+        for batch in hooks.chunk_by(|(a, _), (b, _)| {
+            a.order == b.order
+                && a.parallel_group.is_some()
+                && a.parallel_group == b.parallel_group
+        }) {
+            let outcomes: Vec<_> = if let [(hook, files)] = batch {
+                vec![Self::run_timed(hook, files, &envs, root)]
+            } else {
+                thread::scope(|s| {
+                    let handles: Vec<_> = batch
+                        .iter()
+                        .map(|(hook, files)| {
+                            s.spawn(|| {
+                                Self::run_timed(hook, files, &envs, root)
+                            })
+                        })
+                        .collect();
+                    handles
+                        .into_iter()
+                        .map(|h| h.join().expect("hook thread panicked"))
+                        .collect()
+                })
+            };
+
+            for ((hook, _), (outcome, duration)) in batch.iter().zip(outcomes) {
+                let Err(err) = outcome else {
+                    summary.push((hook, HookStatus::Passed, duration));
+                    continue;
+                };
+                let status = match err.downcast_ref() {
+                    Some(ReviseError::HookTimeout { .. }) => {
+                        HookStatus::TimedOut
+                    }
+                    _ => HookStatus::Failed,
+                };
+                let carry_on = match hook.on_failure {
+                    OnFailure::Abort => false,
+                    OnFailure::Warn => {
+                        eprintln!("{} {err}", "warning:".yellow().bold());
+                        true
+                    }
+                    OnFailure::Prompt => {
+                        Confirm::new(&format!("{err}. Continue anyway?"))
+                            .with_default(false)
+                            .prompt()
+                            .unwrap_or(false)
+                    }
+                };
+                if carry_on {
+                    summary.push((hook, HookStatus::Ignored, duration));
+                } else {
+                    summary.push((hook, status, duration));
+                    if result.is_ok() {
+                        result = Err(err);
+                    }
+                }
+            }
+            if result.is_err() {
+                break;
+            }
+        }
+
+        // skipped hooks were noted first, list everything in run order
+        summary.sort_by_key(|(hook, ..)| {
+            sorted_hooks.iter().position(|h| std::ptr::eq(*h, *hook))
+        });
+        print_summary(phase, &summary);
+        result
+    }
+
+    /// Runs a configured hook with its own `env`, `cwd` and `timeout`, and
+    /// `files` appended when it asks for them.
+    fn run_timed(
+        hook: &Hook,
+        files: &[String],
+        envs: &[(String, String)],
+        root: Option<&Path>,
+    ) -> (ReviseResult<()>, Duration) {
+        let mut envs = envs.to_vec();
+        envs.extend(hook.env.clone());
+        let cwd = match (&hook.cwd, root) {
+            (Some(cwd), Some(root)) => Some(root.join(cwd)),
+            (cwd, root) => cwd.clone().or_else(|| root.map(Path::to_path_buf)),
+        };
+        let mut command = hook.command.clone();
+        if hook.pass_files {
+            for file in files {
+                command.push(' ');
+                command.push_str(&shell_quote(file));
+            }
+        }
+        let start = Instant::now();
+        let result = Self::run_command_in(
+            &command,
+            &envs,
+            cwd.as_deref(),
+            hook.timeout.map(Duration::from_secs),
+        );
+        (result, start.elapsed())
+    }
+
+    /// Runs `command` in a shell with extra environment variables, from `cwd`
+    /// when given. After `timeout` the command and everything it started are
+    /// killed. Only then does it run in its own process group, so other hooks
+    /// can read from the terminal and get Ctrl-C like any foreground command.
+    pub fn run_command_in(
+        command: &str,
+        envs: &[(String, String)],
+        cwd: Option<&Path>,
+        timeout: Option<Duration>,
+    ) -> ReviseResult<()> {
+        let mut cmd = if cfg!(target_os = "windows") {
+            let mut cmd = Command::new("cmd");
+            cmd.arg("/C");
+            cmd
         } else {
-            Command::new("sh")
-                .arg("-c")
-                .arg(command)
-                .stdout(Stdio::inherit())
-                .stderr(Stdio::inherit())
-                .output()?
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c");
+            cmd
+        };
+        cmd.arg(command)
+            .envs(envs.iter().map(|(k, v)| (k, v)))
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit());
+        if let Some(cwd) = cwd {
+            cmd.current_dir(cwd);
+        }
+        #[cfg(unix)]
+        if timeout.is_some() {
+            std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+        }
+        let mut child = cmd.spawn()?;
+
+        let status = match timeout {
+            None => child.wait()?,
+            Some(timeout) => wait_timeout(&mut child, command, timeout)?,
         };
 
-        if !output.status.success() {
-            return Err(anyhow::anyhow!(
-                "Hook failed: {}\nExit code: {:?}\nStderr: {}",
-                command,
-                output.status.code(),
-                String::from_utf8_lossy(&output.stderr)
-            ));
+        if !status.success() {
+            return Err(ReviseError::Hook {
+                command: command.to_string(),
+                code: status.code(),
+            }
+            .into());
         }
 
         Ok(())
     }
 }
 
+/// Waits for a hook started in its own process group, killing it after
+/// `timeout`. The group doesn't get the terminal's SIGINT, so a SIGINT or
+/// SIGTERM we get meanwhile is passed on to it, and the commit is cancelled.
+fn wait_timeout(
+    child: &mut Child,
+    command: &str,
+    timeout: Duration,
+) -> ReviseResult<ExitStatus> {
+    #[cfg(unix)]
+    let signals = signals::Forward::register()?;
+    let start = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        #[cfg(unix)]
+        if signals.forward(child) {
+            child.wait()?;
+            return Err(ReviseError::Cancelled.into());
+        }
+        if start.elapsed() >= timeout {
+            kill_tree(child);
+            return Err(ReviseError::HookTimeout {
+                command: command.to_string(),
+                seconds: timeout.as_secs(),
+            }
+            .into());
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[cfg(unix)]
+mod signals {
+    use std::{
+        io,
+        process::{Child, Command},
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+    };
+
+    use signal_hook::{
+        SigId,
+        consts::{SIGINT, SIGTERM},
+        flag, low_level,
+    };
+
+    /// Catches SIGINT and SIGTERM until dropped, instead of letting them end
+    /// the process.
+    pub struct Forward {
+        caught: Arc<AtomicUsize>,
+        ids: Vec<SigId>,
+    }
+
+    impl Forward {
+        pub fn register() -> io::Result<Self> {
+            let caught = Arc::new(AtomicUsize::new(0));
+            let mut ids = Vec::new();
+            for signal in [SIGINT, SIGTERM] {
+                ids.push(flag::register_usize(
+                    signal,
+                    Arc::clone(&caught),
+                    signal as usize,
+                )?);
+            }
+            Ok(Self { caught, ids })
+        }
+
+        /// Sends a caught signal to the process group of `child`, returning
+        /// whether there was one.
+        pub fn forward(&self, child: &Child) -> bool {
+            let name = match self.caught.swap(0, Ordering::SeqCst) {
+                0 => return false,
+                signal if signal == SIGTERM as usize => "-TERM",
+                _ => "-INT",
+            };
+            let _ = Command::new("kill")
+                .args([name, "--", &format!("-{}", child.id())])
+                .status();
+            true
+        }
+    }
+
+    impl Drop for Forward {
+        fn drop(&mut self) {
+            for id in self.ids.drain(..) {
+                low_level::unregister(id);
+            }
+        }
+    }
+}
+
+/// Kills a hook's shell along with the processes it spawned, which share its
+/// process group on unix.
+fn kill_tree(child: &mut Child) {
+    #[cfg(unix)]
+    let _ = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{}", child.id())])
+        .status();
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+fn shell_quote(arg: &str) -> String {
+    if cfg!(target_os = "windows") {
+        format!("\"{arg}\"")
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+fn print_summary(phase: &HookType, summary: &[(&Hook, HookStatus, Duration)]) {
+    if summary.is_empty() {
+        return;
+    }
+    println!("{}", format!("{} hooks", phase.name()).bold());
+    for (hook, status, duration) in summary {
+        println!(
+            "  {} {:>7.2}s  {}",
+            status.label(),
+            duration.as_secs_f64(),
+            hook.name.as_ref().unwrap_or(&hook.command)
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,4 +551,144 @@ mod tests {
         let result = HookRunner::run_hook(func, (3, 4, 5));
         assert_eq!(result, "result: 12");
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command_with_context() {
+        let ctx = HookContext {
+            commit_type: Some("feat".to_string()),
+            staged_files: vec!["a.rs".to_string(), "b.rs".to_string()],
+            ..Default::default()
+        };
+        let envs = ctx.envs(&HookType::PostCommit);
+        HookRunner::run_command_in(
+            r#"test "$REVISE_HOOK $REVISE_COMMIT_TYPE" = "post-commit feat" \
+               && test "$(echo "$REVISE_STAGED_FILES" | wc -l)" -eq 2 \
+               && test -z "$REVISE_COMMIT_SHA" && test "$(pwd)" = /"#,
+            &envs,
+            Some(Path::new("/")),
+            None,
+        )
+        .unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_phase() {
+        let hook = |command: &str, order, group: Option<&str>| Hook {
+            command: command.to_string(),
+            order: Some(order),
+            parallel_group: group.map(ToString::to_string),
+            timeout: Some(2),
+            ..Default::default()
+        };
+        let start = Instant::now();
+        HookRunner::run_phase(
+            &HookType::PreCommit,
+            &[hook("sleep 1", 1, Some("a")), hook("sleep 1", 1, Some("a"))],
+            &HookContext::default(),
+        )
+        .unwrap();
+        assert!(start.elapsed() < Duration::from_millis(1900));
+
+        let err = HookRunner::run_phase(
+            &HookType::PreCommit,
+            &[hook("sleep 5", 1, None)],
+            &HookContext::default(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(ReviseError::HookTimeout { seconds: 2, .. })
+        ));
+
+        let warn = Hook {
+            on_failure: OnFailure::Warn,
+            ..hook("exit 3", 1, None)
+        };
+        HookRunner::run_phase(
+            &HookType::PreCommit,
+            &[warn],
+            &HookContext::default(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_matching_files() {
+        let ctx = HookContext {
+            branch: Some("release/1.0".to_string()),
+            staged_files: vec![
+                "src/main.rs".to_string(),
+                "docs/guide.md".to_string(),
+            ],
+            ..Default::default()
+        };
+        let hook = |files: &[&str], exclude: &[&str]| Hook {
+            files: files.iter().map(ToString::to_string).collect(),
+            exclude: exclude.iter().map(ToString::to_string).collect(),
+            ..Default::default()
+        };
+
+        let rust = ctx.matching_files(&hook(&["*.rs"], &[])).unwrap();
+        assert_eq!(rust, Some(vec!["src/main.rs".to_string()]));
+        let none = ctx.matching_files(&hook(&["*.py"], &[])).unwrap();
+        assert_eq!(none, None);
+        let all = ctx.matching_files(&hook(&[], &["docs/**"])).unwrap();
+        assert_eq!(all, Some(vec!["src/main.rs".to_string()]));
+
+        let on_release = Hook {
+            branches: vec!["release/*".to_string()],
+            ..Default::default()
+        };
+        assert!(ctx.matching_files(&on_release).unwrap().is_some());
+        let on_main = Hook {
+            branches: vec!["main".to_string()],
+            ..Default::default()
+        };
+        let nested = HookContext {
+            branch: Some("feature/main".to_string()),
+            ..ctx.clone()
+        };
+        assert!(nested.matching_files(&on_main).unwrap().is_none());
+        let nested = HookContext {
+            branch: Some("release/1.0/rc".to_string()),
+            ..ctx.clone()
+        };
+        assert!(nested.matching_files(&on_release).unwrap().is_none());
+        let for_feat = Hook {
+            types: vec!["feat".to_string()],
+            ..Default::default()
+        };
+        assert!(ctx.matching_files(&for_feat).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_hook_filter() {
+        let clippy = Hook {
+            name: Some("clippy".to_string()),
+            command: "cargo clippy".to_string(),
+            ..Default::default()
+        };
+        let filter = HookFilter {
+            skip: vec!["clippy".to_string()],
+            only: vec![HookType::PreCommit],
+            ..Default::default()
+        };
+        assert!(filter.skips(&clippy));
+        assert!(!filter.skips(&Hook::default()));
+        assert!(filter.allows_phase(&HookType::PreCommit));
+        assert!(!filter.allows_phase(&HookType::PostCommit));
+
+        let by_command = HookFilter {
+            skip: vec!["cargo clippy".to_string()],
+            ..Default::default()
+        };
+        assert!(by_command.skips(&clippy));
+        let disabled = HookFilter {
+            disabled: true,
+            ..Default::default()
+        };
+        assert!(!disabled.allows_phase(&HookType::PreCommit));
+    }
 }
@@ -0,0 +1,131 @@
+use std::{fs, path::Path};
+
+use crate::{
+    error::{ReviseError, ReviseResult},
+    git::{GitUtils, repo::GitRepository},
+};
+
+/// Comment written into every script we install, used to recognise them.
+const MARKER: &str = "# installed by git-revise";
+const BACKUP_SUFFIX: &str = "revise-backup";
+
+/// Only a plain `git commit` gets the prompts: `-m`, templates, merges and
+/// amends already have a message. IDEs run hooks without a terminal, in
+/// which case the message is left alone.
+const PREPARE_COMMIT_MSG: &str = r#"[ -z "$2" ] || exit 0
+(exec < /dev/tty) 2>/dev/null || exit 0
+exec "$REVISE" hook prepare-commit-msg "$1" < /dev/tty
+"#;
+
+const COMMIT_MSG: &str = r#"exec "$REVISE" lint "$1"
+"#;
+
+const HOOKS: [(&str, &str); 2] = [
+    ("prepare-commit-msg", PREPARE_COMMIT_MSG),
+    ("commit-msg", COMMIT_MSG),
+];
+
+/// Writes the git hooks into the repository's hooks directory. Existing hooks
+/// that weren't written by us are only replaced with `force`, after being
+/// moved aside.
+pub fn install(force: bool) -> ReviseResult<()> {
+    let dir = GitUtils::git_hooks_dir()?;
+    fs::create_dir_all(&dir)?;
+    let exe = std::env::current_exe()?;
+
+    let foreign: Vec<_> = HOOKS
+        .iter()
+        .map(|(name, _)| dir.join(name))
+        .filter(|path| path.exists() && !is_ours(path))
+        .collect();
+    if !force {
+        if let Some(path) = foreign.first() {
+            return Err(ReviseError::Validation(format!(
+                "{} already exists, pass --force to replace it (the old hook \
+                 is kept with a .{BACKUP_SUFFIX} suffix)",
+                path.display()
+            ))
+            .into());
+        }
+    }
+    for path in foreign {
+        let mut backup = path.clone().into_os_string();
+        backup.push(format!(".{BACKUP_SUFFIX}"));
+        fs::rename(&path, backup)?;
+    }
+
+    for (name, body) in HOOKS {
+        let path = dir.join(name);
+
+        fs::write(&path, script(&exe, body))?;
+        make_executable(&path)?;
+        println!("Installed {}", path.display());
+    }
+    Ok(())
+}
+
+/// Removes the hooks written by [`install`] and puts back what they replaced.
+pub fn uninstall() -> ReviseResult<()> {
+    let dir = GitUtils::git_hooks_dir()?;
+    for (name, _) in HOOKS {
+        let path = dir.join(name);
+        if !is_ours(&path) {
+            continue;
+        }
+        fs::remove_file(&path)?;
+        let backup = dir.join(format!("{name}.{BACKUP_SUFFIX}"));
+        if backup.exists() {
+            fs::rename(&backup, &path)?;
+            println!("Restored {}", path.display());
+        } else {
+            println!("Removed {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// The hook script running `body` with `$REVISE` set to `exe`, or to
+/// `git-revise` from PATH when the binary has moved since.
+fn script(exe: &Path, body: &str) -> String {
+    // git runs hooks with sh on every platform
+    let exe = exe.display().to_string().replace('\'', r"'\''");
+    format!(
+        "#!/bin/sh\n{MARKER}, remove with `git-revise hook uninstall`\n\
+         REVISE='{exe}'\n[ -x \"$REVISE\" ] || REVISE=git-revise\n{body}"
+    )
+}
+
+fn is_ours(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|s| s.contains(MARKER))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> ReviseResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+#[allow(clippy::unnecessary_wraps)]
+const fn make_executable(_path: &Path) -> ReviseResult<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+
+    #[test]
+    fn test_script_quotes_the_binary() {
+        let exe = Path::new("/opt/it's $HOME/git-revise");
+        let script = script(exe, "printf %s \"$REVISE\"\n");
+        let output = Command::new("sh").args(["-c", &script]).output().unwrap();
+        // the binary doesn't exist, so the script falls back to PATH
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "git-revise");
+        assert!(script.contains(r"REVISE='/opt/it'\''s $HOME/git-revise'"));
+    }
+}
@@ -4,6 +4,7 @@ pub mod config;
 pub mod error;
 pub mod git;
 pub mod hook;
+pub mod message;
 pub mod revise;
 
 pub use cli::{AICommand, ReviseCommands};
@@ -1,20 +1,29 @@
-use git_revise::{cli, config, revise::Revise};
+use clap::Parser;
+use git_revise::{cli, config, error, revise::Revise};
 use human_panic::setup_panic;
 
 #[tokio::main]
 #[allow(clippy::needless_return)]
 async fn main() {
     setup_panic!();
-    config::initialize_config().unwrap_or_else(|e| {
-        eprintln!("Load config err: {e}");
-        std::process::exit(exitcode::CONFIG);
-    });
-    let cmd = cli::parse_command();
+    let cli = cli::Cli::parse();
+    if let Err(e) = cli.change_dir() {
+        error::report(&e);
+        std::process::exit(error::exit_code(&e));
+    }
+    if let Err(e) = config::initialize_config() {
+        if !cli.is_config_command() {
+            error::report(&e);
+            std::process::exit(error::exit_code(&e));
+        }
+        config::CFG.get_or_init(config::ReviseConfig::default);
+    }
+    let cmd = cli::parse_command(cli);
     match Revise::default().run(cmd).await {
         Ok(()) => std::process::exit(exitcode::OK),
         Err(e) => {
-            eprintln!("Error occurred when trying to commit, err: {e}");
-            std::process::exit(exitcode::DATAERR)
+            error::report(&e);
+            std::process::exit(error::exit_code(&e))
         }
     }
 }
@@ -0,0 +1,378 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+use thiserror::Error;
+
+use crate::config;
+
+/// Footer tokens that mark a breaking change.
+const BREAKING_TOKENS: [&str; 2] = ["BREAKING CHANGE", "BREAKING-CHANGE"];
+
+/// A commit message split along the conventional commits format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Message {
+    /// Emoji in front of the type, when it is one of the configured emojis.
+    pub emoji: Option<String>,
+    pub commit_type: String,
+    pub scope: Option<String>,
+    /// Whether the header carries the `!` marker.
+    pub breaking: bool,
+    pub subject: String,
+    /// Trailing `(#31, #34)` issue reference of the header.
+    pub issue: Option<String>,
+    pub body: Option<String>,
+    pub footers: Vec<Footer>,
+}
+
+/// A `token: value` (or `token #value`) trailer at the end of the message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Footer {
+    pub token: String,
+    pub value: String,
+    /// 1-based line the footer starts on.
+    pub line: usize,
+}
+
+/// Where and why a message is not a conventional commit. Line and column are
+/// 1-based, columns count characters.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("{line}:{column}: {message}")]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Message {
+    /// Parses `message` using the emojis of the loaded config.
+    pub fn parse(message: &str) -> Result<Self, ParseError> {
+        let cfg = config::get_config();
+        let emojis: Vec<&str> =
+            cfg.emojis.iter().map(|e| e.value.as_str()).collect();
+        Self::parse_with(message, &emojis)
+    }
+
+    /// Parses `message`, stripping any of `emojis` in front of the type.
+    /// Comment lines (starting with `#`) are ignored the way git does.
+    pub fn parse_with(
+        message: &str,
+        emojis: &[&str],
+    ) -> Result<Self, ParseError> {
+        let mut lines = content_lines(message)
+            .skip_while(|(_, line)| line.trim().is_empty());
+
+        let Some((header_line, header)) = lines.next() else {
+            return Err(ParseError {
+                line: 1,
+                column: 1,
+                message: "the message is empty".to_string(),
+            });
+        };
+        let mut parsed = parse_header(header, header_line, emojis)?;
+
+        let rest: Vec<(usize, &str)> = lines.collect();
+        if let Some((line, text)) = rest.first() {
+            if !text.trim().is_empty() {
+                return Err(ParseError {
+                    line: *line,
+                    column: 1,
+                    message: "expected a blank line after the header"
+                        .to_string(),
+                });
+            }
+        }
+
+        let footer_start = footer_start(&rest);
+        let body = rest[..footer_start]
+            .iter()
+            .map(|(_, line)| *line)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let body = body.trim();
+        parsed.body = (!body.is_empty()).then(|| body.to_string());
+        parsed.footers = parse_footers(&rest[footer_start..]);
+        Ok(parsed)
+    }
+
+    /// First footer whose token matches `token`, ignoring case.
+    pub fn footer(&self, token: &str) -> Option<&str> {
+        self.footers
+            .iter()
+            .find(|f| f.token.eq_ignore_ascii_case(token))
+            .map(|f| f.value.as_str())
+    }
+
+    /// Description from the `BREAKING CHANGE` footer.
+    pub fn breaking_change(&self) -> Option<&str> {
+        BREAKING_TOKENS.iter().find_map(|token| self.footer(token))
+    }
+
+    /// Whether the header `!` or a `BREAKING CHANGE` footer is present.
+    pub fn is_breaking(&self) -> bool {
+        self.breaking || self.breaking_change().is_some()
+    }
+
+    /// Values of every `Refs` footer.
+    pub fn refs(&self) -> Vec<&str> {
+        self.values("Refs")
+    }
+
+    /// Values of every `Co-authored-by` footer.
+    pub fn co_authors(&self) -> Vec<&str> {
+        self.values("Co-authored-by")
+    }
+
+    fn values(&self, token: &str) -> Vec<&str> {
+        self.footers
+            .iter()
+            .filter(|f| f.token.eq_ignore_ascii_case(token))
+            .map(|f| f.value.as_str())
+            .collect()
+    }
+}
+
+/// What follows the comment prefix on the line `git commit -v` puts above
+/// the diff.
+const SCISSORS: &str = " ------------------------ >8 ------------------------";
+
+/// A message file the way git cleans it up before committing: everything
+/// from the scissors line of `git commit -v` on is dropped, and so are the
+/// lines starting with `comment`, git's `core.commentChar`.
+pub fn strip_comments(message: &str, comment: &str) -> String {
+    let mut stripped = String::new();
+    for line in message.lines() {
+        if line.strip_prefix(comment) == Some(SCISSORS) {
+            break;
+        }
+        if !line.starts_with(comment) {
+            stripped.push_str(line);
+            stripped.push('\n');
+        }
+    }
+    stripped
+}
+
+/// Lines of `message` that aren't comments, with their 1-based numbers.
+pub fn content_lines(message: &str) -> impl Iterator<Item = (usize, &str)> {
+    message
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line))
+        .filter(|(_, line)| !line.starts_with('#'))
+}
+
+/// The header line of `message` and its 1-based number.
+pub fn header(message: &str) -> Option<(usize, &str)> {
+    content_lines(message).find(|(_, line)| !line.trim().is_empty())
+}
+
+fn parse_header(
+    line: &str,
+    line_no: usize,
+    emojis: &[&str],
+) -> Result<Message, ParseError> {
+    let err = |at: usize, message: &str| ParseError {
+        line: line_no,
+        column: line[..at].chars().count() + 1,
+        message: message.to_string(),
+    };
+    let mut message = Message::default();
+    let mut pos = line.len() - line.trim_start().len();
+
+    // longest first, so "⚡️" wins over a bare "⚡"
+    let mut candidates: Vec<(&str, &str)> = emojis
+        .iter()
+        .flat_map(|e| [(*e, *e), (*e, e.trim_end_matches('\u{fe0f}'))])
+        .filter(|(_, prefix)| !prefix.is_empty())
+        .collect();
+    candidates.sort_by_key(|(_, prefix)| std::cmp::Reverse(prefix.len()));
+    if let Some((emoji, prefix)) = candidates
+        .into_iter()
+        .find(|(_, prefix)| line[pos..].starts_with(prefix))
+    {
+        message.emoji = Some(emoji.to_string());
+        pos += prefix.len();
+        pos += line[pos..].len() - line[pos..].trim_start().len();
+    }
+
+    let rest = &line[pos..];
+    let type_len = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+        .unwrap_or(rest.len());
+    if type_len == 0 {
+        return Err(err(pos, "expected a commit type"));
+    }
+    message.commit_type = rest[..type_len].to_string();
+    pos += type_len;
+
+    if line[pos..].starts_with('(') {
+        let Some(end) = line[pos..].find(')') else {
+            return Err(err(pos, "unclosed `(` around the scope"));
+        };
+        let scope = line[pos + 1..pos + end].trim();
+        if scope.is_empty() {
+            return Err(err(pos + 1, "the scope is empty"));
+        }
+        message.scope = Some(scope.to_string());
+        pos += end + 1;
+    }
+    if line[pos..].starts_with('!') {
+        message.breaking = true;
+        pos += 1;
+    }
+    if !line[pos..].starts_with(':') {
+        return Err(err(pos, "expected `:` after the type"));
+    }
+    pos += 1;
+    if !line[pos..].starts_with(' ') {
+        return Err(err(pos, "expected a space after `:`"));
+    }
+
+    let subject = line[pos..].trim();
+    if subject.is_empty() {
+        return Err(err(pos + 1, "the subject is empty"));
+    }
+    if let Some(caps) = issue_re().captures(subject) {
+        message.issue = Some(caps["issue"].to_string());
+        message.subject = caps["subject"].to_string();
+    } else {
+        message.subject = subject.to_string();
+    }
+    Ok(message)
+}
+
+fn issue_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"^(?P<subject>.*?)\s*\((?P<issue>[^()]*(?:#\d+|[A-Z][A-Z0-9]+-\d+)[^()]*)\)$",
+        )
+        .unwrap()
+    })
+}
+
+fn footer_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"^(?P<token>BREAKING[ -]CHANGE|[A-Za-z][\w-]*)(?:: | #)(?P<value>.*)$",
+        )
+        .unwrap()
+    })
+}
+
+/// Index into `lines` where the footers begin: the trailing paragraphs that
+/// each start with a `token: value` line.
+fn footer_start(lines: &[(usize, &str)]) -> usize {
+    let mut start = lines.len();
+    for idx in (0..lines.len()).rev() {
+        let blank = lines[idx].1.trim().is_empty();
+        let first_of_paragraph =
+            !blank && (idx == 0 || lines[idx - 1].1.trim().is_empty());
+        if !first_of_paragraph {
+            continue;
+        }
+        if !footer_re().is_match(lines[idx].1) {
+            break;
+        }
+        start = idx;
+    }
+    start
+}
+
+fn parse_footers(lines: &[(usize, &str)]) -> Vec<Footer> {
+    let mut footers: Vec<Footer> = Vec::new();
+    for (line, text) in lines {
+        if let Some(caps) = footer_re().captures(text) {
+            footers.push(Footer {
+                token: caps["token"].to_string(),
+                value: caps["value"].trim().to_string(),
+                line: *line,
+            });
+            continue;
+        }
+        // continuation of a multi-line footer
+        if let Some(last) = footers.last_mut() {
+            if !text.trim().is_empty() {
+                last.value.push('\n');
+                last.value.push_str(text.trim_end());
+            }
+        }
+    }
+    footers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMOJIS: [&str; 2] = ["✨", "♻️"];
+
+    #[test]
+    fn test_parse_header() {
+        let m = Message::parse_with("✨ feat(api)!: add users (#12)", &EMOJIS)
+            .unwrap();
+        assert_eq!(m.emoji.as_deref(), Some("✨"));
+        assert_eq!(m.commit_type, "feat");
+        assert_eq!(m.scope.as_deref(), Some("api"));
+        assert!(m.breaking);
+        assert_eq!(m.subject, "add users");
+        assert_eq!(m.issue.as_deref(), Some("#12"));
+
+        // emoji without the variation selector
+        let m = Message::parse_with("♻ refactor: tidy", &EMOJIS).unwrap();
+        assert_eq!(m.emoji.as_deref(), Some("♻️"));
+        assert_eq!(m.commit_type, "refactor");
+    }
+
+    #[test]
+    fn test_parse_body_and_footers() {
+        let m = Message::parse_with(
+            "fix: handle empty diff\n\nThe diff can be empty.\n\nIt \
+             happens.\n\nBREAKING CHANGE: the api\n  changed\nRefs: \
+             #3\nCo-authored-by: A <a@b.c>\n# a comment\n",
+            &EMOJIS,
+        )
+        .unwrap();
+        assert_eq!(
+            m.body.as_deref(),
+            Some("The diff can be empty.\n\nIt happens.")
+        );
+        assert_eq!(m.breaking_change(), Some("the api\n  changed"));
+        assert!(m.is_breaking());
+        assert_eq!(m.refs(), vec!["#3"]);
+        assert_eq!(m.co_authors(), vec!["A <a@b.c>"]);
+        assert_eq!(m.footers[1].line, 9);
+    }
+
+    #[test]
+    fn test_strip_comments() {
+        let verbose = format!(
+            "fix: wrap\n\nBody.\n# Please enter the commit \
+             message\n#{SCISSORS}\n# Do not modify or remove the line \
+             above.\ndiff --git a/x b/x\n"
+        );
+        assert_eq!(strip_comments(&verbose, "#"), "fix: wrap\n\nBody.\n");
+
+        let custom =
+            format!("fix: wrap\n; a comment\n#3 stays\n;{SCISSORS}\n+x\n");
+        assert_eq!(strip_comments(&custom, ";"), "fix: wrap\n#3 stays\n");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |msg| Message::parse_with(msg, &EMOJIS).unwrap_err();
+        let e = err("Update README");
+        assert_eq!((e.line, e.column), (1, 7));
+        let e = err("✨ feat(api: add");
+        assert_eq!((e.line, e.column), (1, 7));
+        let e = err("feat():x");
+        assert_eq!((e.line, e.column), (1, 6));
+        let e = err("feat:add");
+        assert_eq!((e.line, e.column), (1, 6));
+        let e = err("feat: ok\nno blank line");
+        assert_eq!((e.line, e.column), (2, 1));
+        let e = err("\n\n");
+        assert_eq!(e.message, "the message is empty");
+    }
+}
@@ -1,19 +1,31 @@
+pub mod lint;
 pub mod prompts;
+pub mod reword;
 pub mod status;
 pub mod template;
 
-use std::collections::HashMap;
+use std::{collections::HashMap, fs, path::PathBuf};
 
-use inquire::InquireError;
+use colored::Colorize;
+use lint::Linter;
+use reword::Reword;
 use status::Status;
 use template::Template;
 
 use crate::{
-    cli::ReviseCommands,
+    cli::{Commands, HookCommand, ReviseCommands},
     config::{self, Hook},
-    error::ReviseResult,
-    git::GitUtils,
-    hook::{HookRunner, HookType},
+    error::{self, ReviseError, ReviseResult},
+    git::{
+        GitUtils,
+        pathspec::{self, PathMatcher},
+        push::GitPush,
+        rebase::GitRebase,
+        repo::GitRepository,
+    },
+    hook::{HookContext, HookFilter, HookRunner, HookType, install},
+    message::Message,
+    revise::prompts::{Inquire, commit_confirm, commit_hunk, commit_stage},
 };
 
 #[derive(Default, Debug)]
@@ -22,103 +34,289 @@ pub struct Revise {
     pub status: Status,
     pub message: String,
     pub hooks: HashMap<HookType, Vec<Hook>>,
+    pub context: HookContext,
+    pub hook_filter: HookFilter,
 }
 
 impl Revise {
     pub async fn run(&mut self, cmd: ReviseCommands) -> ReviseResult<()> {
         let cfg = config::get_config();
         self.hooks.clone_from(&cfg.hooks);
-        // if message is not empty, return it
-        if let Some(msg) = &cmd.message {
-            self.run_pre_commit_hooks()?;
-            GitUtils::new().commit(msg)?;
-            self.run_post_commit_hooks()?;
-            return Ok(());
+        self.hook_filter.clone_from(&cmd.hooks);
+        self.context.repo_root =
+            GitUtils::new().repo_root().ok().map(PathBuf::from);
+        match &cmd.command {
+            Some(Commands::Reword(args)) => {
+                return Reword::new(args)
+                    .run(&cmd.excludes)
+                    .await
+                    .map_err(error::map_cancelled);
+            }
+            Some(Commands::Lint(args)) => return Linter::new(args).run(),
+            Some(Commands::Merge(args)) => {
+                return self.merge("merge", &args.args);
+            }
+            Some(Commands::Pull(args)) => {
+                return self.merge("pull", &args.args);
+            }
+            Some(Commands::Hook { command }) => {
+                return self.run_git_hook(command, &cmd).await;
+            }
+            Some(Commands::Config { command }) => {
+                return config::command::run(command);
+            }
+            None => {}
         }
-
         if !cmd.add.is_empty() {
-            self.run_pre_add_hooks()?;
+            self.run_hooks(&HookType::PreAdd)?;
             GitUtils::new().add(&cmd.add)?;
-            self.run_post_add_hooks()?;
+            self.run_hooks(&HookType::PostAdd)?;
         }
-        self.run_pre_commit_hooks()?;
-        match self.template.run(&cmd).await {
-            Ok(msg) => {
-                GitUtils::new().commit(&msg)?;
-                self.run_post_commit_hooks()?;
-                Ok(())
-            }
-            Err(err) => {
-                if let Some(
-                    InquireError::OperationCanceled
-                    | InquireError::OperationInterrupted,
-                ) = err.downcast_ref()
-                {
-                    Ok(())
-                } else {
-                    Err(err)
+        if cmd.patch {
+            self.run_hooks(&HookType::PreAdd)?;
+            self.stage_hunks(&cmd)?;
+            self.run_hooks(&HookType::PostAdd)?;
+        }
+        if !cmd.amend {
+            self.ensure_staged(&cmd)?;
+        }
+        // the `[path]` sections depend on what was just staged
+        config::apply_staged()?;
+        let cfg = config::get_config();
+        self.hooks.clone_from(&cfg.hooks);
+        self.template = if cmd.amend {
+            // pre-fill every prompt with what is already in HEAD
+            Template::from_message(&GitUtils::new().head_message()?)
+        } else {
+            Template::default()
+        };
+
+        if let Some(msg) = &cmd.message {
+            self.set_message(msg)?;
+        }
+        // without `-m` the type is only known after the prompts, so the hooks
+        // filtered by `types` run then
+        let typed = |hook: &Hook| !hook.types.is_empty();
+        let prompted = cmd.message.is_none();
+        self.run_hooks_where(&HookType::PreCommit, |h| {
+            !(prompted && typed(h))
+        })?;
+        let msg = if let Some(msg) = &cmd.message {
+            msg.clone()
+        } else {
+            let msg = self
+                .template
+                .run(&cmd)
+                .await
+                .map_err(error::map_cancelled)?;
+            self.set_message(&msg)?;
+            self.run_hooks_where(&HookType::PreCommit, typed)?;
+            msg
+        };
+        let msg = self.run_commit_msg_hooks(msg, cmd.message.is_none())?;
+        self.context.staged_files = GitUtils::new().staged_files()?;
+        Self::commit(&cmd, &msg)?;
+        self.context.commit_sha = GitUtils::git_rev_parse("HEAD").ok();
+        self.run_hooks(&HookType::PostCommit)?;
+        // an amended commit would need a force push, leave that to the user
+        if cfg.auto.git.push && !cmd.amend {
+            self.push();
+        }
+        Ok(())
+    }
+
+    async fn run_git_hook(
+        &mut self,
+        command: &HookCommand,
+        cmd: &ReviseCommands,
+    ) -> ReviseResult<()> {
+        match command {
+            HookCommand::Install { force } => install::install(*force),
+            HookCommand::Uninstall => install::uninstall(),
+            HookCommand::PrepareCommitMsg { file, source } => {
+                if source.is_some() {
+                    return Ok(());
                 }
+                let msg = self
+                    .template
+                    .run(cmd)
+                    .await
+                    .map_err(error::map_cancelled)?;
+                // keep git's comments below the message for the editor
+                let original = fs::read_to_string(file)?;
+                fs::write(file, format!("{msg}\n{original}"))?;
+                Ok(())
             }
         }
     }
 
-    pub fn run_pre_commit_hooks(&self) -> ReviseResult<()> {
-        let hooks = self.hooks.get(&HookType::PreCommit);
-        let mut sorted_hooks: Vec<_> = hooks.unwrap().iter().collect();
-        sorted_hooks.sort_by_key(|h| h.order.unwrap_or(u32::MAX));
+    /// Exposes `message` to the hooks that follow, parsed into the
+    /// `REVISE_*` variables and written to `REVISE_MESSAGE_FILE`.
+    fn set_message(&mut self, message: &str) -> ReviseResult<()> {
+        let parsed = Message::parse(message).ok();
+        self.context.commit_type =
+            parsed.as_ref().map(|m| m.commit_type.clone());
+        self.context.scope = parsed.as_ref().and_then(|m| m.scope.clone());
+        self.context.subject = parsed
+            .map(|m| m.subject)
+            .or_else(|| message.trim().lines().next().map(ToString::to_string));
 
-        for hook in sorted_hooks {
-            if hook.skip.unwrap_or(false) {
-                println!("Skipping hook: {}", hook.command);
-                continue;
-            }
-            HookRunner::run_hook(HookRunner::run_command, &hook.command)?;
-        }
+        let file = PathBuf::from(GitUtils::git_path("REVISE_MSG")?);
+        fs::write(&file, message)?;
+        self.context.message_file = Some(file.canonicalize()?);
         Ok(())
     }
 
-    pub fn run_post_commit_hooks(&self) -> ReviseResult<()> {
-        let hooks = self.hooks.get(&HookType::PostCommit);
-        let mut sorted_hooks: Vec<_> = hooks.unwrap().iter().collect();
-        sorted_hooks.sort_by_key(|h| h.order.unwrap_or(u32::MAX));
+    /// Lets the commit-msg hooks rewrite `msg` through the message file, or
+    /// reject it by failing. A rewritten message is shown for confirmation
+    /// again when it came from the prompts.
+    fn run_commit_msg_hooks(
+        &mut self,
+        msg: String,
+        confirm: bool,
+    ) -> ReviseResult<String> {
+        self.run_hooks(&HookType::CommitMsg)?;
+        let Some(file) = &self.context.message_file else {
+            return Ok(msg);
+        };
+        let rewritten = fs::read_to_string(file)?;
+        if rewritten.trim_end() == msg.trim_end() {
+            return Ok(msg);
+        }
+
+        let rewritten = if confirm {
+            println!("The commit-msg hooks changed the message");
+            let mut confirm = commit_confirm::Part::from_message(rewritten);
+            confirm.inquire().map_err(error::map_cancelled)?;
+            confirm.ans.unwrap()
+        } else {
+            rewritten
+        };
+        self.set_message(&rewritten)?;
+        Ok(rewritten)
+    }
+
+    fn commit(cmd: &ReviseCommands, msg: &str) -> ReviseResult<()> {
+        if cmd.amend {
+            GitUtils::new().amend(msg, cmd.no_verify)
+        } else {
+            GitUtils::new().commit(msg, cmd.no_verify)
+        }
+    }
 
-        for hook in sorted_hooks {
-            if hook.skip.unwrap_or(false) {
-                println!("Skipping hook: {}", hook.command);
-                continue;
+    /// Lets the user pick unstaged hunks file by file, like `git add -p`.
+    pub fn stage_hunks(&self, cmd: &ReviseCommands) -> ReviseResult<()> {
+        let git = GitUtils::new();
+        let files = git.unstaged_hunks(&cmd.excludes)?;
+        if files.is_empty() {
+            println!("No unstaged hunks to pick from");
+            return Ok(());
+        }
+
+        for file in files {
+            let mut hunks = commit_hunk::Part::new(file);
+            hunks.inquire().map_err(error::map_cancelled)?;
+            if let Some(selected) = &hunks.ans {
+                git.apply_cached(&hunks.file.patch(selected))?;
             }
-            HookRunner::run_hook(HookRunner::run_command, &hook.command)?;
         }
         Ok(())
     }
 
-    pub fn run_pre_add_hooks(&self) -> ReviseResult<()> {
-        let hooks = self.hooks.get(&HookType::PreAdd);
-        let mut sorted_hooks: Vec<_> = hooks.unwrap().iter().collect();
-        sorted_hooks.sort_by_key(|h| h.order.unwrap_or(u32::MAX));
+    /// Makes sure there is something to commit before any prompt is shown,
+    /// offering to stage changed files when the index is empty.
+    pub fn ensure_staged(&self, cmd: &ReviseCommands) -> ReviseResult<()> {
+        let git = GitUtils::new();
+        if !git.staged_files()?.is_empty() {
+            return Ok(());
+        }
 
-        for hook in sorted_hooks {
-            if hook.skip.unwrap_or(false) {
-                println!("Skipping hook: {}", hook.command);
-                continue;
-            }
-            HookRunner::run_hook(HookRunner::run_command, &hook.command)?;
+        let excludes = PathMatcher::new(&cmd.excludes)?;
+        let candidates: Vec<_> = git
+            .status()?
+            .into_iter()
+            .filter(|e| e.has_unstaged() && !excludes.is_match(&e.path))
+            .collect();
+        if candidates.is_empty() {
+            return Err(ReviseError::NothingStaged.into());
         }
+
+        let mut stage = commit_stage::Part::new(candidates);
+        stage.inquire().map_err(error::map_cancelled)?;
+        let Some(paths) = stage.ans else {
+            return Err(ReviseError::NothingStaged.into());
+        };
+
+        // status paths are relative to the root, not to the current directory
+        let paths: Vec<String> =
+            paths.iter().map(|p| pathspec::top(p)).collect();
+        self.run_hooks(&HookType::PreAdd)?;
+        git.add(&paths)?;
+        self.run_hooks(&HookType::PostAdd)?;
         Ok(())
     }
 
-    pub fn run_post_add_hooks(&self) -> ReviseResult<()> {
-        let hooks = self.hooks.get(&HookType::PostAdd);
-        let mut sorted_hooks: Vec<_> = hooks.unwrap().iter().collect();
-        sorted_hooks.sort_by_key(|h| h.order.unwrap_or(u32::MAX));
+    /// Runs the configured hooks of `phase` by their `order` from the repo
+    /// root, stopping at the first failure.
+    pub fn run_hooks(&self, phase: &HookType) -> ReviseResult<()> {
+        self.run_hooks_where(phase, |_| true)
+    }
 
-        for hook in sorted_hooks {
-            if hook.skip.unwrap_or(false) {
-                println!("Skipping hook: {}", hook.command);
-                continue;
-            }
-            HookRunner::run_hook(HookRunner::run_command, &hook.command)?;
+    /// [`run_hooks`](Self::run_hooks), for the hooks `keep` selects.
+    fn run_hooks_where(
+        &self,
+        phase: &HookType,
+        keep: impl Fn(&Hook) -> bool,
+    ) -> ReviseResult<()> {
+        let hooks: Vec<&Hook> = self
+            .hooks
+            .get(phase)
+            .into_iter()
+            .flatten()
+            .filter(|hook| keep(hook))
+            .collect();
+        if hooks.is_empty() || !self.hook_filter.allows_phase(phase) {
+            return Ok(());
         }
-        Ok(())
+        let hooks: Vec<Hook> = hooks
+            .into_iter()
+            .cloned()
+            .map(|mut hook| {
+                if self.hook_filter.skips(&hook) {
+                    hook.skip = Some(true);
+                }
+                hook
+            })
+            .collect();
+
+        let mut context = self.context.clone();
+        // only asked for once hooks run, so `config` or `lint` work anywhere
+        context.branch = GitUtils::git_current_branch().ok().flatten();
+        // once committed the index is clean, keep what went into the commit
+        if context.commit_sha.is_none() {
+            context.staged_files = GitUtils::new().staged_files()?;
+        }
+        HookRunner::run_phase(phase, &hooks, &context)
+    }
+
+    /// Pushes the new commit after the pre-push hooks. The commit is already
+    /// made, so a failure here is only reported.
+    pub fn push(&self) {
+        let pushed = self
+            .run_hooks(&HookType::PrePush)
+            .and_then(|()| GitUtils::git_push());
+        if let Err(err) = pushed {
+            eprintln!(
+                "{} the commit was created but not pushed: {err}",
+                "warning:".yellow().bold()
+            );
+        }
+    }
+
+    /// Runs `git merge` or `git pull`, then the post-merge hooks.
+    pub fn merge(&self, command: &str, args: &[String]) -> ReviseResult<()> {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        GitUtils::git_interactive(command, &args)?;
+        self.run_hooks(&HookType::PostMerge)
     }
 }
@@ -0,0 +1,411 @@
+use std::{
+    fs,
+    io::{self, Read},
+};
+
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::{
+    cli::{LintArgs, LintFormat},
+    config::{self, ReviseConfig, Severity},
+    error::{ReviseError, ReviseResult},
+    git::{GitUtils, rebase::GitRebase},
+    message::{self, Message},
+};
+
+/// Every rule, as named in `[lint.rules]`.
+pub const RULES: [&str; 9] = [
+    "header-format",
+    "type-enum",
+    "scope-enum",
+    "subject-max-length",
+    "subject-case",
+    "subject-full-stop",
+    "body-max-line-length",
+    "issue-required",
+    "breaking-change",
+];
+
+/// Headers of messages git writes itself, which aren't linted.
+const GENERATED: [&str; 5] =
+    ["Merge ", "Revert \"", "fixup!", "squash!", "amend!"];
+
+/// A rule violation found in one message.
+#[derive(Debug, Clone, Serialize)]
+pub struct Problem {
+    /// Where the message came from: a file, `stdin` or a commit.
+    pub source: String,
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+/// Checks commit messages against the `[lint]` rules of the config.
+#[derive(Debug, Clone)]
+pub struct Linter {
+    pub args: LintArgs,
+}
+
+impl Linter {
+    pub fn new(args: &LintArgs) -> Self {
+        Self { args: args.clone() }
+    }
+
+    pub fn run(&self) -> ReviseResult<()> {
+        let cfg = config::get_config();
+        let mut problems = Vec::new();
+        for (source, message) in self.messages()? {
+            problems.extend(lint(&message, cfg).into_iter().map(|p| Problem {
+                source: source.clone(),
+                ..p
+            }));
+        }
+
+        match self.args.format {
+            LintFormat::Text => print_text(&problems),
+            LintFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&problems)?);
+            }
+        }
+
+        let errors = problems
+            .iter()
+            .filter(|p| p.severity == Severity::Error)
+            .count();
+        if errors > 0 {
+            return Err(ReviseError::Validation(format!(
+                "Commit message lint failed with {errors} error(s)"
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// The messages to check, each with a label for the report.
+    fn messages(&self) -> ReviseResult<Vec<(String, String)>> {
+        if let Some(range) = &self.args.range {
+            let commits = if range.contains("..") {
+                GitUtils::git_rev_list_no_merges(range)?
+            } else {
+                vec![GitUtils::git_rev_parse(range)?]
+            };
+            return commits
+                .into_iter()
+                .map(|sha| {
+                    let message = GitUtils::git_commit_message(&sha)?;
+                    Ok((sha.get(..7).unwrap_or(&sha).to_string(), message))
+                })
+                .collect();
+        }
+
+        // a message file may be git's own, with comments and the diff of
+        // `git commit -v`
+        let comment = comment_char();
+        match self.args.file.as_deref() {
+            Some(path) if path != "-" => {
+                let message = fs::read_to_string(path)?;
+                let message = message::strip_comments(&message, &comment);
+                Ok(vec![(path.to_string(), message)])
+            }
+            _ => {
+                let mut message = String::new();
+                io::stdin().read_to_string(&mut message)?;
+                let message = message::strip_comments(&message, &comment);
+                Ok(vec![("stdin".to_string(), message)])
+            }
+        }
+    }
+}
+
+/// Whether `message` was written by git: a merge, a revert, or a
+/// `fixup!`, `squash!` or `amend!` commit for `git rebase --autosquash`.
+pub fn is_generated(message: &str) -> bool {
+    message::header(message).is_some_and(|(_, header)| {
+        GENERATED.iter().any(|prefix| header.starts_with(prefix))
+    })
+}
+
+/// git's `core.commentChar`, `#` unless set. With `auto` git picks a
+/// character the message doesn't use, which is `#` in the common case.
+fn comment_char() -> String {
+    GitUtils::git_output(&["config", "core.commentChar"])
+        .ok()
+        .filter(|c| !c.is_empty() && c != "auto")
+        .unwrap_or_else(|| "#".to_string())
+}
+
+/// Runs every rule on `message`. The `source` of the problems is left empty,
+/// and messages git generates have none.
+pub fn lint(message: &str, cfg: &ReviseConfig) -> Vec<Problem> {
+    if is_generated(message) {
+        return Vec::new();
+    }
+    let mut report = Report {
+        rules: &cfg.lint,
+        problems: Vec::new(),
+    };
+    let emojis: Vec<&str> =
+        cfg.emojis.iter().map(|e| e.value.as_str()).collect();
+    match Message::parse_with(message, &emojis) {
+        Ok(parsed) => {
+            report.header(message, &parsed, cfg);
+            report.body(message, &parsed);
+        }
+        Err(err) => report.add(
+            "header-format",
+            Severity::Error,
+            (err.line, err.column),
+            err.message,
+        ),
+    }
+    report.problems
+}
+
+struct Report<'a> {
+    rules: &'a config::Lint,
+    problems: Vec<Problem>,
+}
+
+impl Report<'_> {
+    /// Records a problem unless its rule is turned off.
+    fn add(
+        &mut self,
+        rule: &'static str,
+        default: Severity,
+        (line, column): (usize, usize),
+        message: String,
+    ) {
+        let severity = self.rules.severity(rule, default);
+        if severity != Severity::Off {
+            self.problems.push(Problem {
+                source: String::new(),
+                rule,
+                severity,
+                line,
+                column,
+                message,
+            });
+        }
+    }
+
+    /// Checks each scope of `feat(api,web)` against the configured ones.
+    fn scopes(&mut self, message: &str, parsed: &Message, cfg: &ReviseConfig) {
+        let scopes = cfg.get_scopes();
+        let Some(scope) = parsed.scope.as_ref() else {
+            return;
+        };
+        // detected scopes depend on the work tree, so any scope may be valid
+        if scopes.is_empty() || cfg.detect_scopes {
+            return;
+        }
+        let (line, header) = message::header(message).unwrap_or((1, ""));
+        let open = header.find('(').unwrap_or_default();
+        for scope in scope.split(',').map(str::trim) {
+            if scopes.iter().any(|s| s == scope) {
+                continue;
+            }
+            let idx = header[open..].find(scope).map_or(open, |idx| open + idx);
+            self.add(
+                "scope-enum",
+                Severity::Warning,
+                (line, header[..idx].chars().count() + 1),
+                format!("scope `{scope}` is not one of: {}", scopes.join(", ")),
+            );
+        }
+    }
+
+    fn header(&mut self, message: &str, parsed: &Message, cfg: &ReviseConfig) {
+        let (line, header) = message::header(message).unwrap_or((1, ""));
+        let at = |idx: Option<usize>| {
+            (line, idx.map_or(1, |idx| header[..idx].chars().count() + 1))
+        };
+
+        if !cfg.types.iter().any(|t| t.key == parsed.commit_type) {
+            self.add(
+                "type-enum",
+                Severity::Error,
+                at(header.find(&parsed.commit_type)),
+                format!(
+                    "type `{}` is not one of: {}",
+                    parsed.commit_type,
+                    cfg.types
+                        .iter()
+                        .map(|t| t.key.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            );
+        }
+
+        self.scopes(message, parsed, cfg);
+
+        let subject = at(header.find(": ").map(|idx| idx + 2));
+        let subject_len = parsed.subject.chars().count();
+        if subject_len > self.rules.subject_max_length {
+            self.add(
+                "subject-max-length",
+                Severity::Error,
+                subject,
+                format!(
+                    "subject is {subject_len} characters long, the limit is {}",
+                    self.rules.subject_max_length
+                ),
+            );
+        }
+        if parsed.subject.starts_with(char::is_uppercase) {
+            self.add(
+                "subject-case",
+                Severity::Warning,
+                subject,
+                "subject should start with a lowercase letter".to_string(),
+            );
+        }
+        if parsed.subject.ends_with('.') {
+            self.add(
+                "subject-full-stop",
+                Severity::Warning,
+                (line, subject.1 + subject_len - 1),
+                "subject should not end with a period".to_string(),
+            );
+        }
+
+        let has_issue = parsed.issue.is_some()
+            || ["Refs", "Closes", "Fixes"]
+                .iter()
+                .any(|token| parsed.footer(token).is_some());
+        if !has_issue {
+            self.add(
+                "issue-required",
+                Severity::Off,
+                (line, 1),
+                "reference an issue in the header or a `Refs:` footer"
+                    .to_string(),
+            );
+        }
+
+        match (parsed.breaking, parsed.breaking_change()) {
+            (true, None) => self.add(
+                "breaking-change",
+                Severity::Warning,
+                at(header.find("!:")),
+                "`!` marks a breaking change, describe it in a `BREAKING \
+                 CHANGE:` footer"
+                    .to_string(),
+            ),
+            (false, Some(_)) => self.add(
+                "breaking-change",
+                Severity::Warning,
+                at(header.find(':')),
+                "the message has a `BREAKING CHANGE:` footer, mark the header \
+                 with `!`"
+                    .to_string(),
+            ),
+            _ => {}
+        }
+    }
+
+    fn body(&mut self, message: &str, parsed: &Message) {
+        let max = self.rules.body_max_line_length;
+        let header_line = message::header(message).map_or(1, |(line, _)| line);
+        let body_end = parsed.footers.first().map_or(usize::MAX, |f| f.line);
+        for (line, text) in message::content_lines(message)
+            .filter(|(line, _)| *line > header_line && *line < body_end)
+        {
+            let len = text.chars().count();
+            // long URLs and the like can't be wrapped
+            if len > max && text.contains(' ') {
+                self.add(
+                    "body-max-line-length",
+                    Severity::Warning,
+                    (line, max + 1),
+                    format!(
+                        "body line is {len} characters long, wrap it at {max}"
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn print_text(problems: &[Problem]) {
+    for p in problems {
+        let severity = match p.severity {
+            Severity::Error => "error".red().bold(),
+            _ => "warning".yellow().bold(),
+        };
+        println!(
+            "{}:{}:{}: {severity}[{}] {}",
+            p.source, p.line, p.column, p.rule, p.message
+        );
+    }
+    if problems.is_empty() {
+        return;
+    }
+    let errors = problems
+        .iter()
+        .filter(|p| p.severity == Severity::Error)
+        .count();
+    println!(
+        "{} error(s), {} warning(s)",
+        errors,
+        problems.len() - errors
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(message: &str) -> Vec<(&'static str, usize, usize)> {
+        lint(message, &ReviseConfig::default())
+            .into_iter()
+            .map(|p| (p.rule, p.line, p.column))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint() {
+        assert!(rules("feat(api): add users\n\nRefs: #1").is_empty());
+        assert_eq!(rules("Update README"), vec![("header-format", 1, 7)]);
+        assert_eq!(
+            rules("wip: Add users."),
+            vec![
+                ("type-enum", 1, 1),
+                ("subject-case", 1, 6),
+                ("subject-full-stop", 1, 15)
+            ]
+        );
+        assert_eq!(rules("feat!: drop v1"), vec![("breaking-change", 1, 5)]);
+        for generated in [
+            "Merge branch 'main' into dev",
+            "Revert \"feat: add users\"\n\nThis reverts commit 1234567.",
+            "fixup! feat: add users",
+            "squash! feat: add users",
+            "amend! feat: add users\n\nfeat: add user accounts",
+            "# Please enter the commit message\nMerge tag 'v1.0'",
+        ] {
+            assert!(rules(generated).is_empty(), "{generated}");
+        }
+        assert!(!rules("Reverted the users").is_empty());
+
+        let long = format!("fix: wrap\n\n{}", "word ".repeat(30));
+        assert_eq!(rules(&long), vec![("body-max-line-length", 3, 101)]);
+
+        let cfg = ReviseConfig {
+            scopes: vec!["api".to_string(), "web".to_string()],
+            ..ReviseConfig::default()
+        };
+        assert!(lint("feat(api,web): add users", &cfg).is_empty());
+        let problems = lint("feat(api, cli): add users", &cfg);
+        assert_eq!(
+            problems
+                .iter()
+                .map(|p| (p.rule, p.column))
+                .collect::<Vec<_>>(),
+            vec![("scope-enum", 11)]
+        );
+    }
+}
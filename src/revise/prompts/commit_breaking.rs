@@ -31,7 +31,9 @@ impl Default for Part {
 
 impl Inquire for Part {
     fn inquire(&mut self) -> ReviseResult<()> {
+        let initial = self.ans.clone().unwrap_or_default();
         let ans = Editor::new(&self.msg)
+            .with_predefined_text(&initial)
             .with_formatter(&|submission| {
                 let char_count = submission.chars().count();
                 if char_count == 0 {
@@ -50,7 +52,7 @@ impl Inquire for Part {
             .prompt()?;
 
         match &*ans {
-            "<skipped>" | "" => {}
+            "<skipped>" | "" => self.ans = None,
             _ => {
                 self.ans = Some(ans);
             }
@@ -3,7 +3,7 @@ use inquire::CustomType;
 
 use super::Inquire;
 use crate::{
-    error::ReviseResult,
+    error::{ReviseError, ReviseResult},
     revise::{prompts::commit_edit, status::Status, template::Template},
 };
 
@@ -11,16 +11,30 @@ use crate::{
 pub struct Part {
     pub msg: String,
     pub ans: Option<String>,
-    pub template: Template,
+    /// What is shown, possibly colored.
+    pub preview: String,
+    /// What is committed on submit.
+    pub message: String,
 }
 
 impl Part {
-    pub fn new(template: Template) -> Self {
+    pub fn new(template: &Template) -> Self {
+        Self::with_preview(template.template(true), template.to_string())
+    }
+
+    /// Confirms a message that didn't come out of the template, e.g. one a
+    /// hook rewrote.
+    pub fn from_message(message: String) -> Self {
+        Self::with_preview(message.clone(), message)
+    }
+
+    fn with_preview(preview: String, message: String) -> Self {
         Self {
             msg: "Provide a LONGER description of the change (optional):"
                 .to_string(),
             ans: None,
-            template,
+            preview,
+            message,
         }
     }
 }
@@ -33,7 +47,7 @@ impl Inquire for Part {
             .black()
             .bold()
             .italic(),
-            self.template.template(true),
+            self.preview,
             "\n\n###--------------------------------------------------------###\n"
             .black()
             .bold()
@@ -49,13 +63,13 @@ impl Inquire for Part {
 
         match ans {
             Status::Edit => {
-                let mut cedit =
-                    commit_edit::Part::new(self.template.to_string());
+                let mut cedit = commit_edit::Part::new(self.message.clone());
                 cedit.inquire().unwrap();
                 self.ans = cedit.ans;
             }
-            _ => self.ans = Some(self.template.to_string()),
-        };
+            Status::Abort => return Err(ReviseError::Cancelled.into()),
+            Status::Submit => self.ans = Some(self.message.clone()),
+        }
         Ok(())
     }
 }
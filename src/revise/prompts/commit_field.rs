@@ -0,0 +1,82 @@
+use inquire::{Confirm, Select, Text, required, validator::Validation};
+use regex::Regex;
+use serde_json::Value;
+
+use super::Inquire;
+use crate::{
+    config::{Field, FieldKind},
+    error::ReviseResult,
+};
+
+/// A custom question from `[[fields]]`.
+#[derive(Debug, Clone)]
+pub struct Part {
+    pub field: Field,
+    /// A string, or a boolean for a confirm field.
+    pub ans: Option<Value>,
+}
+
+impl Part {
+    pub const fn new(field: Field) -> Self {
+        Self { field, ans: None }
+    }
+}
+
+impl Inquire for Part {
+    fn inquire(&mut self) -> ReviseResult<()> {
+        let field = &self.field;
+        let msg = field
+            .message
+            .clone()
+            .unwrap_or_else(|| format!("{}:", field.name));
+        self.ans = match field.kind {
+            FieldKind::Text => {
+                let initial = self
+                    .ans
+                    .as_ref()
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                let mut prompt = Text::new(&msg).with_initial_value(initial);
+                if field.required {
+                    prompt = prompt
+                        .with_validator(required!("An answer is required"));
+                }
+                if let Some(pattern) = &field.validate {
+                    let regex = Regex::new(pattern)?;
+                    let pattern = pattern.clone();
+                    prompt = prompt.with_validator(move |ans: &str| {
+                        Ok(if ans.is_empty() || regex.is_match(ans) {
+                            Validation::Valid
+                        } else {
+                            Validation::Invalid(
+                                format!("The answer has to match {pattern}")
+                                    .into(),
+                            )
+                        })
+                    });
+                }
+                let ans = prompt.prompt()?;
+                Some(Value::String(ans)).filter(|a| a != "")
+            }
+            FieldKind::Select => {
+                let cursor = self
+                    .ans
+                    .as_ref()
+                    .and_then(Value::as_str)
+                    .and_then(|ans| field.options.iter().position(|o| o == ans))
+                    .unwrap_or(0);
+                let ans = Select::new(&msg, field.options.clone())
+                    .with_starting_cursor(cursor)
+                    .prompt()?;
+                Some(Value::String(ans))
+            }
+            FieldKind::Confirm => {
+                let default =
+                    self.ans.as_ref().and_then(Value::as_bool).unwrap_or(false);
+                let ans = Confirm::new(&msg).with_default(default).prompt()?;
+                Some(Value::Bool(ans))
+            }
+        };
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use colored::Colorize;
+use inquire::MultiSelect;
+
+use super::Inquire;
+use crate::{error::ReviseResult, git::hunk::FileDiff};
+
+#[derive(Debug, Clone)]
+pub struct Part {
+    pub msg: String,
+    pub ans: Option<Vec<usize>>,
+    pub file: FileDiff,
+}
+
+impl Part {
+    pub fn new(file: FileDiff) -> Self {
+        Self {
+            msg: format!("Select the hunks of {} to stage:", file.path),
+            ans: None,
+            file,
+        }
+    }
+
+    fn print_preview(&self) {
+        println!("\n{}", self.file.path.bold());
+        for (idx, hunk) in self.file.hunks.iter().enumerate() {
+            let label = format!("[{}]", idx + 1);
+            println!("{} {}", label.bold(), hunk.header.cyan());
+            for line in &hunk.lines {
+                let line = if line.starts_with('+') {
+                    line.green()
+                } else if line.starts_with('-') {
+                    line.red()
+                } else {
+                    line.normal()
+                };
+                println!("    {line}");
+            }
+        }
+    }
+}
+
+impl Inquire for Part {
+    fn inquire(&mut self) -> ReviseResult<()> {
+        self.print_preview();
+        let options: Vec<String> = self
+            .file
+            .hunks
+            .iter()
+            .enumerate()
+            .map(|(idx, hunk)| format!("[{}] {}", idx + 1, hunk.summary()))
+            .collect();
+        let selected = MultiSelect::new(&self.msg, options)
+            .with_help_message(
+                "space to select, → to all, ← to none, enter to confirm",
+            )
+            .raw_prompt()?;
+        let hunks: Vec<usize> = selected.into_iter().map(|o| o.index).collect();
+        self.ans = Some(hunks).filter(|h| !h.is_empty());
+        Ok(())
+    }
+}
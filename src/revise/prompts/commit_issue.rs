@@ -1,19 +1,33 @@
-use inquire::Text;
+use inquire::{Text, required};
 
 use super::Inquire;
-use crate::error::ReviseResult;
+use crate::{
+    config::{self, Severity},
+    error::ReviseResult,
+};
 
 #[derive(Debug, Clone)]
 pub struct Part {
     pub msg: String,
     pub ans: Option<String>,
+    /// Set when the `issue-required` lint rule is an error.
+    pub required: bool,
 }
 
 impl Part {
     pub fn new() -> Self {
+        let cfg = config::get_config();
+        let required = cfg.lint.severity("issue-required", Severity::Off)
+            == Severity::Error;
+        let msg = if required {
+            "List the ISSUES of this change (required). E.g.= #31, #34:"
+        } else {
+            "List any ISSUES by this change. E.g.= #31, #34:"
+        };
         Self {
-            msg: "List any ISSUES by this change. E.g.= #31, #34:".to_string(),
+            msg: msg.to_string(),
             ans: None,
+            required,
         }
     }
 }
@@ -26,7 +40,12 @@ impl Default for Part {
 
 impl Inquire for Part {
     fn inquire(&mut self) -> ReviseResult<()> {
-        let ans = Text::new(&self.msg)
+        let initial = self.ans.clone().unwrap_or_default();
+        let mut prompt = Text::new(&self.msg).with_initial_value(&initial);
+        if self.required {
+            prompt = prompt.with_validator(required!("An issue is required"));
+        }
+        let ans = prompt
             .with_formatter(&|submission| {
                 let char_count = submission.chars().count();
                 if char_count == 0 {
@@ -39,9 +58,9 @@ impl Inquire for Part {
             })
             .prompt()?;
         match &*ans {
-            "<skipped>" | "" => {}
+            "<skipped>" | "" => self.ans = None,
             _ => self.ans = Some(ans),
-        };
+        }
         Ok(())
     }
 }
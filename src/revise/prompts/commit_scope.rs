@@ -1,36 +1,33 @@
-use inquire::{Select, Text};
+use std::path::PathBuf;
+
+use inquire::{MultiSelect, Text};
 
 use super::Inquire;
 use crate::{
-    config::{self},
+    config::{self, scope::Scopes},
     error::ReviseResult,
+    git::GitUtils,
 };
 
 #[derive(Debug, Clone)]
 pub struct Part {
     pub msg: String,
     pub ans: Option<String>,
-    pub options: Vec<String>,
+    pub scopes: Scopes,
 }
 
 impl Part {
     pub fn new() -> Self {
         let cfg = config::get_config();
-        let mut options: Vec<String> = cfg.get_scopes();
-        // Prepend "empty" if not present
-        if !options.contains(&"empty".to_string()) {
-            options.insert(0, "empty".to_string());
-        }
-
-        // Append "custom" if not present
-        if !options.contains(&"custom".to_string()) {
-            options.push("custom".to_string());
-        }
-
+        let root = cfg
+            .detect_scopes
+            .then(|| GitUtils::new().repo_root().ok())
+            .flatten()
+            .map(PathBuf::from);
         Self {
             msg: "Denote the SCOPE of this change (optional):".to_string(),
             ans: None,
-            options,
+            scopes: Scopes::new(cfg, root.as_deref()),
         }
     }
 }
@@ -43,16 +40,55 @@ impl Default for Part {
 
 impl Inquire for Part {
     fn inquire(&mut self) -> ReviseResult<()> {
-        let mut ans = Select::new(&self.msg, self.options.clone()).prompt()?;
-
-        if ans == "custom" {
-            ans = Text::new("Denote the SCOPE of this change:").prompt()?;
-            self.ans = Some(ans).filter(|a| !a.is_empty());
-        } else if ans == "empty" {
-            self.ans = None;
-        } else {
-            self.ans = Some(ans);
+        let mut options = self.scopes.names.clone();
+        // an earlier answer, or else the scopes of the staged files
+        let selected = match &self.ans {
+            Some(ans) => split(ans),
+            None => self
+                .scopes
+                .touched(&GitUtils::new().staged_files().unwrap_or_default()),
         };
+        for scope in selected.iter().rev() {
+            // keep a custom scope from an earlier answer selectable
+            if !options.contains(scope) {
+                options.insert(0, scope.clone());
+            }
+        }
+        if !options.contains(&"custom".to_string()) {
+            options.push("custom".to_string());
+        }
+        let defaults: Vec<usize> = options
+            .iter()
+            .enumerate()
+            .filter(|(_, o)| selected.contains(o))
+            .map(|(idx, _)| idx)
+            .collect();
+
+        let mut ans = MultiSelect::new(&self.msg, options)
+            .with_default(&defaults)
+            .with_help_message(
+                "↑↓ to move, space to select, enter to confirm, none for no \
+                 scope",
+            )
+            .prompt()?;
+
+        if let Some(idx) = ans.iter().position(|a| a == "custom") {
+            ans.remove(idx);
+            let custom =
+                Text::new("Denote the SCOPE of this change:").prompt()?;
+            ans.extend(split(&custom));
+        }
+        self.ans = Some(ans.join(",")).filter(|a| !a.is_empty());
         Ok(())
     }
 }
+
+/// The scopes of `api, web`.
+fn split(scopes: &str) -> Vec<String> {
+    scopes
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
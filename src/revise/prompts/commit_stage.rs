@@ -0,0 +1,41 @@
+use inquire::MultiSelect;
+
+use super::Inquire;
+use crate::{error::ReviseResult, git::status::StatusEntry};
+
+#[derive(Debug, Clone)]
+pub struct Part {
+    pub msg: String,
+    pub ans: Option<Vec<String>>,
+    pub entries: Vec<StatusEntry>,
+}
+
+impl Part {
+    pub fn new(entries: Vec<StatusEntry>) -> Self {
+        Self {
+            msg: "Nothing is staged yet, select the files to stage:"
+                .to_string(),
+            ans: None,
+            entries,
+        }
+    }
+}
+
+impl Inquire for Part {
+    fn inquire(&mut self) -> ReviseResult<()> {
+        let options: Vec<String> =
+            self.entries.iter().map(ToString::to_string).collect();
+        let selected = MultiSelect::new(&self.msg, options)
+            .with_help_message(
+                "↑↓ to move, space to select, → to all, ← to none, enter to \
+                 confirm",
+            )
+            .raw_prompt()?;
+        let paths: Vec<String> = selected
+            .into_iter()
+            .map(|option| self.entries[option.index].path.clone())
+            .collect();
+        self.ans = Some(paths).filter(|p| !p.is_empty());
+        Ok(())
+    }
+}
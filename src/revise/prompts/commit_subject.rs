@@ -30,7 +30,9 @@ impl Default for Part {
 
 impl Inquire for Part {
     fn inquire(&mut self) -> ReviseResult<()> {
+        let initial = self.ans.clone().unwrap_or_default();
         let ans = Text::new(&self.msg)
+            .with_initial_value(&initial)
             .with_help_message("Infinity more chars allowed")
             .with_validator(|s: &str| {
                 if s.is_empty() {
@@ -35,17 +35,23 @@ impl Default for Part {
 
 impl Inquire for Part {
     fn inquire(&mut self) -> ReviseResult<()> {
-        let ans = Select::new(&self.msg, self.options.clone()).prompt()?;
+        let cfg = config::get_config();
+        let cursor = self
+            .ans
+            .as_ref()
+            .and_then(|key| cfg.types.iter().position(|t| &t.key == key))
+            .unwrap_or(0);
+        let ans = Select::new(&self.msg, self.options.clone())
+            .with_starting_cursor(cursor)
+            .prompt()?;
         let idx =
             self.options.iter().position(|s| *s == ans).ok_or_else(|| {
                 anyhow!(
-                    "Error: committing type '{}' not found in the options.",
-                    ans
+                    "Error: committing type '{ans}' not found in the options."
                 )
             })?;
-        let cfg = config::get_config();
         self.ans = Some(cfg.get_type_key(idx).ok_or_else(|| {
-            anyhow!("Error: no type key found at position {}.", idx)
+            anyhow!("Error: no type key found at position {idx}.")
         })?);
         Ok(())
     }
@@ -0,0 +1,250 @@
+use std::{
+    fmt::Write,
+    fs,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use colored::Colorize;
+use inquire::Select;
+
+use super::template::Template;
+use crate::{
+    ai::redact,
+    cli::RewordArgs,
+    config,
+    error::{ReviseError, ReviseResult},
+    git::{GitUtils, rebase::GitRebase},
+};
+
+const KEEP: &str = "Keep";
+const REWORD: &str = "Reword";
+const REWORD_AI: &str = "Reword with AI";
+
+/// Rewrites the messages of existing commits on the current branch.
+#[derive(Debug, Clone)]
+pub struct Reword {
+    pub range: String,
+    pub force: bool,
+}
+
+impl Reword {
+    pub fn new(args: &RewordArgs) -> Self {
+        Self {
+            range: args.range.clone(),
+            force: args.force,
+        }
+    }
+
+    pub async fn run(&self, excludes: &[String]) -> ReviseResult<()> {
+        let (base, commits) = self.plan()?;
+
+        let mut messages = Vec::with_capacity(commits.len());
+        for (idx, sha) in commits.iter().enumerate() {
+            let template =
+                Template::from_message(&GitUtils::git_commit_message(sha)?);
+            println!(
+                "\n{} {} ({}/{})\n{}",
+                "Commit".bold(),
+                short(sha).yellow(),
+                idx + 1,
+                commits.len(),
+                describe(&template)
+            );
+            messages.push(Self::reword_one(sha, template, excludes).await?);
+        }
+
+        let count = messages.iter().flatten().count();
+        if count == 0 {
+            println!("Nothing to reword");
+            return Ok(());
+        }
+        Self::apply(&base, &commits, &messages)?;
+        println!("Reworded {count} commit(s)");
+        Ok(())
+    }
+
+    /// Resolves the range and checks that rewriting it is safe. Returns the
+    /// rebase base and the commits to go through, oldest first.
+    fn plan(&self) -> ReviseResult<(String, Vec<String>)> {
+        if GitUtils::git_is_dirty()? {
+            return Err(ReviseError::Validation(
+                "The working tree has uncommitted changes, commit or stash \
+                 them first"
+                    .to_string(),
+            )
+            .into());
+        }
+
+        let (from, to) = match self.range.split_once("..") {
+            Some((from, "")) => (from, "HEAD"),
+            Some((from, to)) => (from, to),
+            None => (self.range.as_str(), "HEAD"),
+        };
+        if from.is_empty() {
+            return Err(ReviseError::Validation(
+                "The range needs a start, e.g. `HEAD~3..`".to_string(),
+            )
+            .into());
+        }
+        let resolve = |rev: &str| {
+            GitUtils::git_rev_parse(rev).map_err(|_err| {
+                ReviseError::Validation(format!("Unknown revision: {rev}"))
+            })
+        };
+        let base = resolve(from)?;
+        if resolve(to)? != resolve("HEAD")? {
+            return Err(ReviseError::Validation(
+                "Only commits of the current branch can be reworded, the \
+                 range has to end at HEAD"
+                    .to_string(),
+            )
+            .into());
+        }
+
+        let commits = GitUtils::git_rev_list(&format!("{base}..HEAD"))?;
+        if commits.is_empty() {
+            return Err(ReviseError::Validation(format!(
+                "No commits in {}",
+                self.range
+            ))
+            .into());
+        }
+        for sha in &commits {
+            if GitUtils::git_parent_count(sha)? > 1 {
+                return Err(ReviseError::Validation(format!(
+                    "Merge commit {} can't be reworded",
+                    short(sha)
+                ))
+                .into());
+            }
+        }
+
+        if !self.force {
+            for sha in &commits {
+                let remotes = GitUtils::git_remote_branches_containing(sha)?;
+                if !remotes.is_empty() {
+                    return Err(ReviseError::Validation(format!(
+                        "Commit {} is already on {}, rewording it rewrites \
+                         published history; pass --force to do it anyway",
+                        short(sha),
+                        remotes.join(", ")
+                    ))
+                    .into());
+                }
+            }
+        }
+
+        Ok((base, commits))
+    }
+
+    /// Asks what to do with the commit, then runs the prompts pre-filled
+    /// with its current message.
+    async fn reword_one(
+        sha: &str,
+        mut template: Template,
+        excludes: &[String],
+    ) -> ReviseResult<Option<String>> {
+        let mut options = vec![KEEP, REWORD];
+        if config::get_config().api_key.contains_key("gemini_key") {
+            options.push(REWORD_AI);
+        }
+        let action =
+            Select::new("What do you want to do with this commit?", options)
+                .prompt()?;
+
+        match action {
+            REWORD => template.run_default()?,
+            REWORD_AI => {
+                let cfg = config::get_config();
+                let diff = GitUtils::git_commit_diff(sha, excludes)?;
+                let excludes = [excludes, &cfg.ai_exclude].concat();
+                let diff =
+                    redact::scrub_diff(&diff, cfg.secret_scan, &excludes)?;
+                template.run_ai(Template::gemini()?, diff).await?;
+            }
+            _ => return Ok(None),
+        }
+
+        match template.confirm() {
+            Ok(msg) => Ok(Some(msg)),
+            // answering "no" in the confirm step keeps the commit as it is
+            Err(err)
+                if matches!(
+                    err.downcast_ref(),
+                    Some(ReviseError::Cancelled)
+                ) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Replays the commits on top of `base`, amending the message of every
+    /// reworded one. The old tip is saved under `refs/revise/backup/` first.
+    fn apply(
+        base: &str,
+        commits: &[String],
+        messages: &[Option<String>],
+    ) -> ReviseResult<()> {
+        let dir = PathBuf::from(GitUtils::git_path("revise-reword")?);
+        fs::create_dir_all(&dir)?;
+        let dir = dir.canonicalize()?;
+
+        let mut todo = String::new();
+        for (sha, message) in commits.iter().zip(messages) {
+            writeln!(todo, "pick {sha}")?;
+            if let Some(message) = message {
+                fs::write(dir.join(format!("{sha}.msg")), message)?;
+                writeln!(
+                    todo,
+                    "exec git commit --amend --allow-empty --no-verify \
+                     --cleanup=whitespace -F \"$REVISE_TODO_DIR/{sha}.msg\""
+                )?;
+            }
+        }
+        let todo_file = dir.join("git-rebase-todo");
+        fs::write(&todo_file, todo)?;
+
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let backup = format!("refs/revise/backup/{stamp}");
+        GitUtils::git_update_ref(&backup, "HEAD")?;
+
+        let result = GitUtils::git_rebase_with_todo(base, &todo_file);
+        let _ = fs::remove_dir_all(&dir);
+        println!("The previous history is saved as {}", backup.yellow());
+        result
+    }
+}
+
+/// The fields of a parsed message that are set, one per line, with the lines
+/// of the body indented under it.
+fn describe(template: &Template) -> String {
+    let fields = [
+        ("type", &template.commit_type.ans),
+        ("scope", &template.commit_scope.ans),
+        ("subject", &template.commit_subject.ans),
+        ("body", &template.commit_body.ans),
+        ("breaking", &template.commit_breaking.ans),
+        ("issue", &template.commit_issue.ans),
+    ];
+    let mut out = String::new();
+    for (name, value) in fields {
+        let Some(value) = value else {
+            continue;
+        };
+        for (idx, line) in value.trim_end().lines().enumerate() {
+            let label = format!("{:<9}", if idx == 0 { name } else { "" });
+            let _ = writeln!(out, "  {}{line}", label.dimmed());
+        }
+    }
+    out
+}
+
+fn short(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
@@ -5,19 +5,20 @@ use tera::{Context, Tera};
 use tokio::task;
 
 use super::prompts::{
-    commit_ai, commit_body, commit_breaking, commit_confirm, commit_issue,
-    commit_scope, commit_subject, commit_translate, commit_type,
+    commit_ai, commit_body, commit_breaking, commit_confirm, commit_field,
+    commit_issue, commit_scope, commit_subject, commit_translate, commit_type,
 };
 use crate::{
     AICommand, ReviseCommands,
-    ai::{AI, gemini::Gemini},
+    ai::{AI, gemini::Gemini, redact},
     config,
-    error::ReviseResult,
+    error::{ReviseError, ReviseResult},
     git::GitUtils,
+    message::Message,
     revise::prompts::Inquire,
 };
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Clone)]
 pub struct Template {
     pub commit_type: commit_type::Part,
     pub commit_scope: commit_scope::Part,
@@ -25,6 +26,27 @@ pub struct Template {
     pub commit_body: commit_body::Part,
     pub commit_breaking: commit_breaking::Part,
     pub commit_issue: commit_issue::Part,
+    /// The `[[fields]]` of the config.
+    pub fields: Vec<commit_field::Part>,
+}
+
+impl Default for Template {
+    fn default() -> Self {
+        Self {
+            commit_type: commit_type::Part::default(),
+            commit_scope: commit_scope::Part::default(),
+            commit_subject: commit_subject::Part::default(),
+            commit_body: commit_body::Part::default(),
+            commit_breaking: commit_breaking::Part::default(),
+            commit_issue: commit_issue::Part::default(),
+            fields: config::get_config()
+                .fields
+                .iter()
+                .cloned()
+                .map(commit_field::Part::new)
+                .collect(),
+        }
+    }
 }
 
 impl Template {
@@ -34,53 +56,105 @@ impl Template {
         } else {
             self.run_default()?;
         }
-        let mut confirm = commit_confirm::Part::new(self.clone());
+        self.confirm()
+    }
+
+    /// Shows the rendered message and returns it once the user accepts it,
+    /// possibly after editing it by hand.
+    pub fn confirm(&self) -> ReviseResult<String> {
+        let mut confirm = commit_confirm::Part::new(self);
         confirm.inquire()?;
         Ok(confirm.ans.unwrap())
     }
 
+    /// Asks the questions of `prompts`, in order.
     pub fn run_default(&mut self) -> ReviseResult<()> {
-        self.commit_type.inquire()?;
-        self.commit_scope.inquire()?;
-        self.commit_subject.inquire()?;
-        self.commit_body.inquire()?;
-        self.commit_breaking.inquire()?;
-        self.commit_issue.inquire()?;
+        for name in config::get_config().prompt_order() {
+            self.ask(&name)?;
+        }
         Ok(())
     }
 
+    /// Asks the built-in question or field called `name`. Unknown names are
+    /// reported by `config validate` and skipped here.
+    fn ask(&mut self, name: &str) -> ReviseResult<()> {
+        match name {
+            "type" => self.commit_type.inquire(),
+            "scope" => self.commit_scope.inquire(),
+            "subject" => self.commit_subject.inquire(),
+            "body" => self.commit_body.inquire(),
+            "breaking" => self.commit_breaking.inquire(),
+            "issue" => self.commit_issue.inquire(),
+            _ => match self.fields.iter_mut().find(|f| f.field.name == name) {
+                Some(field) => field.inquire(),
+                None => Ok(()),
+            },
+        }
+    }
+
     pub async fn run_action(
         &mut self,
         cmd: &ReviseCommands,
     ) -> ReviseResult<()> {
         let cfg = config::get_config();
-        let Some(key) = cfg.api_key.get("gemini_key") else {
-            return Err(anyhow::anyhow!("API key not found"));
-        };
+        let gemini = Self::gemini()?;
 
-        let gemini = Gemini::new(key);
-
-        let mut s = match cmd.ai.clone().unwrap() {
+        let s = match cmd.ai.clone().unwrap() {
+            AICommand::Translate(s) if s.is_empty() => {
+                let mut translate = commit_translate::Part::new();
+                translate.inquire()?;
+                translate.ans.ok_or_else(|| {
+                    ReviseError::Validation(
+                        "Translate message is empty".to_string(),
+                    )
+                })?
+            }
             AICommand::Translate(s) => s,
-            AICommand::Generate => GitUtils::new().diff(&cmd.excludes)?,
-        };
-        // Fix: If the diff is empty, not directly ask user to input
-        if s.is_empty() {
-            let mut translate = commit_translate::Part::new();
-            translate.inquire()?;
-            s = match translate.ans {
-                Some(s) => s,
-                None => {
-                    return Err(anyhow::anyhow!("Translate message is empty"));
+            AICommand::Generate => {
+                let diff = GitUtils::new().diff(&cmd.excludes)?;
+                let excludes =
+                    [cmd.excludes.as_slice(), &cfg.ai_exclude].concat();
+                let diff =
+                    redact::scrub_diff(&diff, cfg.secret_scan, &excludes)?;
+                if diff.trim().is_empty() {
+                    return Err(ReviseError::Validation(
+                        "Every staged change is excluded from the AI diff"
+                            .to_string(),
+                    )
+                    .into());
                 }
-            };
-        }
+                diff
+            }
+        };
+        self.run_ai(gemini, s).await
+    }
+
+    pub fn gemini() -> ReviseResult<Gemini> {
+        let cfg = config::get_config();
+        let Some(key) = cfg.api_key.get("gemini_key") else {
+            return Err(ReviseError::AiAuth(
+                "api_key.gemini_key is not set".to_string(),
+            )
+            .into());
+        };
+        Ok(Gemini::new(key))
+    }
+
+    /// Asks the remaining questions while the AI works on `input`, then lets
+    /// the user pick one of its suggestions as subject and body.
+    pub async fn run_ai(
+        &mut self,
+        gemini: Gemini,
+        input: String,
+    ) -> ReviseResult<()> {
         let handle =
-            task::spawn(async move { gemini.generate_response(&s).await });
-        self.commit_type.inquire()?;
-        self.commit_scope.inquire()?;
-        self.commit_breaking.inquire()?;
-        self.commit_issue.inquire()?;
+            task::spawn(async move { gemini.generate_response(&input).await });
+        for name in config::get_config().prompt_order() {
+            // the AI writes those
+            if name != "subject" && name != "body" {
+                self.ask(&name)?;
+            }
+        }
         let res = handle.await??;
         let mut ai = commit_ai::Part::new(res.keys().cloned().collect());
         ai.inquire()?;
@@ -90,17 +164,40 @@ impl Template {
         Ok(())
     }
 
+    /// Splits an existing message back into its parts, so it can be used to
+    /// pre-fill the prompts. A message that isn't a conventional commit ends
+    /// up as subject and body.
+    pub fn from_message(message: &str) -> Self {
+        Message::parse(message).map_or_else(
+            |_| {
+                let message = message.trim();
+                let (header, rest) =
+                    message.split_once('\n').unwrap_or((message, ""));
+                let mut template = Self::default();
+                template.commit_subject.ans = Some(header.trim().to_string());
+                template.commit_body.ans =
+                    Some(rest.trim().to_string()).filter(|b| !b.is_empty());
+                template
+            },
+            Self::from,
+        )
+    }
+
+    /// The type, empty when `prompts` leaves it out.
     pub fn get_ctype(&self) -> String {
-        self.commit_type.ans.clone().unwrap()
+        self.commit_type.ans.clone().unwrap_or_default()
     }
     pub fn get_cicon(&self) -> String {
-        config::get_config().get_emoji(&self.get_ctype()).unwrap()
+        // types added by a config don't need an emoji
+        config::get_config()
+            .get_emoji(&self.get_ctype())
+            .unwrap_or_default()
     }
     pub fn get_cscope(&self) -> Option<String> {
         self.commit_scope.ans.clone()
     }
     pub fn get_csubject(&self) -> String {
-        self.commit_subject.ans.clone().unwrap()
+        self.commit_subject.ans.clone().unwrap_or_default()
     }
     pub fn get_cbody(&self) -> Option<String> {
         self.commit_body.ans.clone()
@@ -183,11 +280,42 @@ impl Template {
             }
             ctx.insert("commit_issue", &self.get_cissue());
         }
+        for field in &self.fields {
+            ctx.insert(&field.field.name, &field.ans);
+        }
 
         tera.render("template", &ctx).unwrap()
     }
 }
 
+impl From<Message> for Template {
+    fn from(message: Message) -> Self {
+        let mut template = Self::default();
+        template.commit_breaking.ans =
+            message.breaking_change().map(ToString::to_string);
+        // the template only renders the breaking footer, keep the other
+        // trailers as part of the body so they survive a round trip
+        let footers: Vec<String> = message
+            .footers
+            .iter()
+            .filter(|f| !f.token.starts_with("BREAKING"))
+            .map(|f| format!("{}: {}", f.token, f.value))
+            .collect();
+        let body = message
+            .body
+            .into_iter()
+            .chain((!footers.is_empty()).then(|| footers.join("\n")))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        template.commit_type.ans = Some(message.commit_type);
+        template.commit_scope.ans = message.scope;
+        template.commit_subject.ans = Some(message.subject);
+        template.commit_body.ans = Some(body).filter(|b| !b.is_empty());
+        template.commit_issue.ans = message.issue;
+        template
+    }
+}
+
 impl std::fmt::Display for Template {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let msg = self.template(false);
@@ -195,7 +323,7 @@ impl std::fmt::Display for Template {
     }
 }
 
-#[ignore]
+#[ignore = "requires an interactive config environment"]
 #[test]
 fn test_template() {
     config::initialize_config().unwrap_or_else(|e| {
@@ -228,8 +356,57 @@ fn test_template() {
             ans: Some("#34".to_string()),
             ..Default::default()
         },
+        fields: Vec::new(),
     };
     let s = t.template(true);
     println!("{s}");
     println!("{t}");
 }
+
+/// The config of the tests below, which share the global one.
+#[cfg(test)]
+fn test_config() {
+    config::CFG.get_or_init(|| config::ReviseConfig {
+        scopes: vec!["config".to_string(), "cli".to_string()],
+        ..config::ReviseConfig::default()
+    });
+}
+
+#[test]
+fn test_default_scopes() {
+    test_config();
+    let t = Template::default();
+    assert_eq!(t.commit_scope.scopes.names, vec!["config", "cli"]);
+    assert!(!t.commit_scope.msg.is_empty());
+}
+
+#[test]
+fn test_from_message() {
+    test_config();
+
+    let t = Template::from_message(
+        "✨ feat(config)!: add layered config(#31, #34)   \n\nmerge all \
+         files\n\nBREAKING CHANGE: drop the old lookup\n",
+    );
+    assert_eq!(t.commit_type.ans.as_deref(), Some("feat"));
+    assert_eq!(t.commit_scope.ans.as_deref(), Some("config"));
+    assert_eq!(t.commit_subject.ans.as_deref(), Some("add layered config"));
+    assert_eq!(t.commit_issue.ans.as_deref(), Some("#31, #34"));
+    assert_eq!(t.commit_body.ans.as_deref(), Some("merge all files"));
+    assert_eq!(
+        t.commit_breaking.ans.as_deref(),
+        Some("drop the old lookup")
+    );
+
+    let t = Template::from_message(
+        "fix: typo\n\nRefs: #3\nCo-authored-by: A <a@b.c>",
+    );
+    assert_eq!(
+        t.commit_body.ans.as_deref(),
+        Some("Refs: #3\nCo-authored-by: A <a@b.c>")
+    );
+
+    let t = Template::from_message("Update README");
+    assert_eq!(t.commit_type.ans, None);
+    assert_eq!(t.commit_subject.ans.as_deref(), Some("Update README"));
+}